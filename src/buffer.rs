@@ -1,28 +1,36 @@
+use core::borrow::{Borrow, BorrowMut};
 use core::cell::UnsafeCell;
-use core::mem::size_of;
-use core::mem::transmute;
-use core::ops::{Deref, DerefMut};
-use core::sync::atomic::Ordering;
+use core::hash::{Hash, Hasher};
+use core::mem::{transmute, ManuallyDrop, MaybeUninit};
+use core::ops::{Bound, Deref, DerefMut, RangeBounds};
 
-use crate::Inner;
+use crate::{Drain, Inner, Pool, RawBuffer, SharedBuffer, StaticLease};
 
-/// A statically allocated buffer.
-pub struct Buffer {
+/// A statically allocated buffer, taken from a [`Pool`].
+pub type Buffer = ScopedBuffer<'static>;
+
+/// A buffer that can't outlive the pool it was taken from.
+///
+/// The buffers of a [`Pool`] live as long as the program and are called [`Buffer`], the
+/// buffers of a [`ScopedPool`](crate::ScopedPool) borrow the pool.
+pub struct ScopedBuffer<'a> {
+    /// Raw pointer to the slice backing the buffer.
+    pub(crate) ptr: *mut u8,
     /// The starting index of the slice backing the buffer.
     pub(crate) data: usize,
     /// The length of this buffer.
     pub(crate) len: usize,
     /// The memory pool of which this buffer is part of.
-    pub(crate) pool: &'static UnsafeCell<Inner>,
+    pub(crate) pool: &'a UnsafeCell<Inner>,
 }
 
-impl core::fmt::Debug for Buffer {
+impl core::fmt::Debug for ScopedBuffer<'_> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_list().entries(&self[..]).finish()
     }
 }
 
-impl Deref for Buffer {
+impl Deref for ScopedBuffer<'_> {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
@@ -31,33 +39,131 @@ impl Deref for Buffer {
     }
 }
 
-impl DerefMut for Buffer {
+impl DerefMut for ScopedBuffer<'_> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         let len = self.len;
         &mut self.slice_mut()[..len]
     }
 }
 
-impl Buffer {
+impl AsRef<[u8]> for ScopedBuffer<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl AsMut<[u8]> for ScopedBuffer<'_> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl Borrow<[u8]> for ScopedBuffer<'_> {
+    fn borrow(&self) -> &[u8] {
+        self
+    }
+}
+
+impl BorrowMut<[u8]> for ScopedBuffer<'_> {
+    fn borrow_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl PartialEq for ScopedBuffer<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl Eq for ScopedBuffer<'_> {}
+
+impl PartialOrd for ScopedBuffer<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScopedBuffer<'_> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self[..].cmp(&other[..])
+    }
+}
+
+impl Hash for ScopedBuffer<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self[..].hash(state)
+    }
+}
+
+/// Implement `PartialEq` in both directions between `ScopedBuffer` and a byte slice type.
+macro_rules! impl_partial_eq {
+    ($([$($generics:tt)*] $other:ty),* $(,)?) => {
+        $(
+            impl<$($generics)*> PartialEq<$other> for ScopedBuffer<'_> {
+                fn eq(&self, other: &$other) -> bool {
+                    self[..] == other[..]
+                }
+            }
+
+            impl<$($generics)*> PartialEq<ScopedBuffer<'_>> for $other {
+                fn eq(&self, other: &ScopedBuffer<'_>) -> bool {
+                    self[..] == other[..]
+                }
+            }
+        )*
+    };
+}
+
+impl_partial_eq! {
+    [] [u8],
+    [] &[u8],
+    [const N: usize] [u8; N],
+    [const N: usize] &[u8; N],
+}
+
+impl<'a> IntoIterator for &'a ScopedBuffer<'_> {
+    type Item = &'a u8;
+    type IntoIter = core::slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut ScopedBuffer<'_> {
+    type Item = &'a mut u8;
+    type IntoIter = core::slice::IterMut<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a> ScopedBuffer<'a> {
     /// Create a new buffer.
-    pub(crate) fn new(data: usize, pool: &'static UnsafeCell<Inner>) -> Self {
-        Buffer { data, len: 0, pool }
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the slice with the given data index of a pool with `pool` as
+    /// its inner data structure.
+    pub(crate) unsafe fn new(ptr: *mut u8, data: usize, pool: &'a UnsafeCell<Inner>) -> Self {
+        ScopedBuffer {
+            ptr,
+            data,
+            len: 0,
+            pool,
+        }
     }
 
     /// Get a reference to the slice backing the buffer.
     fn slice(&self) -> &[u8] {
-        unsafe {
-            let data = ((*self.pool.get()).get_ptr)(self.data);
-            core::slice::from_raw_parts(data, (*self.pool.get()).capacity)
-        }
+        unsafe { core::slice::from_raw_parts(self.ptr, self.capacity()) }
     }
 
     /// Get a mutable reference to the slice backing the buffer.
-    fn slice_mut(&mut self) -> &mut [u8] {
-        unsafe {
-            let data = ((*self.pool.get()).get_ptr)(self.data);
-            core::slice::from_raw_parts_mut(data, (*self.pool.get()).capacity)
-        }
+    pub(crate) fn slice_mut(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr, self.capacity()) }
     }
 
     /// Returns the capacity of the buffer.
@@ -70,6 +176,18 @@ impl Buffer {
         self.len
     }
 
+    /// Returns a raw pointer to the start of the buffer. The pointer is aligned to the
+    /// alignment of the pool, see [`pool!`](crate::pool).
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    /// Returns a mutable raw pointer to the start of the buffer. The pointer is aligned
+    /// to the alignment of the pool, see [`pool!`](crate::pool).
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr
+    }
+
     /// # Safety
     ///
     /// New length must be smaller than buffer capacity.
@@ -110,14 +228,7 @@ impl Buffer {
     /// capacity of the buffer, an error is returned containing the byte that
     /// could not be written.
     pub fn push(&mut self, byte: u8) -> Result<(), u8> {
-        if self.len < self.capacity() {
-            let len = self.len;
-            self.slice_mut()[len] = byte;
-            self.len += 1;
-            Ok(())
-        } else {
-            Err(byte)
-        }
+        self.push_within(self.capacity(), byte)
     }
 
     /// Pop the last byte from the buffer. If the buffer is empty, `None` is returned.
@@ -133,20 +244,84 @@ impl Buffer {
 
     /// Resize the buffer. Returns an error with the number of bytes that could not be written.
     pub fn resize(&mut self, size: usize) -> Result<(), usize> {
+        self.resize_within(self.capacity(), size)
+    }
+
+    /// Append the slice to the buffer. If this would exceed the capacity of the buffer,
+    /// an error will be returned containing a slice of the bytes that could not be written.
+    pub fn extend_from_slice<'b>(&mut self, other: &'b [u8]) -> Result<(), &'b [u8]> {
+        self.extend_from_slice_within(self.capacity(), other)
+    }
+
+    /// Returns the remaining space of the buffer, past its length. Bytes written to it can
+    /// be appended to the buffer with [`advance`](Buffer::advance).
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        let len = self.len;
+        let spare = &mut self.slice_mut()[len..];
+        unsafe { core::slice::from_raw_parts_mut(spare.as_mut_ptr().cast(), spare.len()) }
+    }
+
+    /// Append the first `n` bytes of the remaining space to the buffer, after they've been
+    /// written through [`spare_capacity_mut`](Buffer::spare_capacity_mut). The memory of a
+    /// pool is always initialized, so bytes that were not written are left with whatever
+    /// value the slot held before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` exceeds the remaining space of the buffer.
+    pub fn advance(&mut self, n: usize) {
+        assert!(n <= self.remaining(), "cannot advance past capacity");
+        self.len += n;
+    }
+
+    /// Let `f` write directly into the remaining space of the buffer and append the
+    /// number of bytes it returns. The remaining space is not zeroed beforehand.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns more than the remaining space of the buffer.
+    pub fn fill_with(&mut self, f: impl FnOnce(&mut [u8]) -> usize) -> usize {
+        let len = self.len;
+        let n = f(&mut self.slice_mut()[len..]);
+        self.advance(n);
+        n
+    }
+
+    /// Get a mutable reference to the first `capacity` bytes of the slice backing the
+    /// buffer.
+    fn slice_within(&mut self, capacity: usize) -> &mut [u8] {
+        debug_assert!(capacity <= self.capacity());
+        unsafe { core::slice::from_raw_parts_mut(self.ptr, capacity) }
+    }
+
+    /// [`push`](Buffer::push) for a buffer with the given capacity, for wrappers that know
+    /// the capacity without reading it from the pool.
+    pub(crate) fn push_within(&mut self, capacity: usize, byte: u8) -> Result<(), u8> {
+        if self.len < capacity {
+            let len = self.len;
+            self.slice_within(capacity)[len] = byte;
+            self.len += 1;
+            Ok(())
+        } else {
+            Err(byte)
+        }
+    }
+
+    /// [`resize`](Buffer::resize) for a buffer with the given capacity.
+    pub(crate) fn resize_within(&mut self, capacity: usize, size: usize) -> Result<(), usize> {
         if size < self.len {
             self.len = size;
             Ok(())
-        } else if size <= self.capacity() {
+        } else if size <= capacity {
             let len = self.len;
-            for byte in &mut self.slice_mut()[len..size] {
+            for byte in &mut self.slice_within(capacity)[len..size] {
                 *byte = 0x00;
             }
             self.len = size;
             Ok(())
         } else {
             let len = self.len;
-            let capacity = self.capacity();
-            for byte in &mut self.slice_mut()[len..capacity] {
+            for byte in &mut self.slice_within(capacity)[len..capacity] {
                 *byte = 0x00;
             }
             self.len = capacity;
@@ -154,15 +329,19 @@ impl Buffer {
         }
     }
 
-    /// Append the slice to the buffer. If this would exceed the capacity of the buffer,
-    /// an error will be returned containing a slice of the bytes that could not be written.
-    pub fn extend_from_slice<'a>(&mut self, other: &'a [u8]) -> Result<(), &'a [u8]> {
-        let remaining_capacity = self.remaining();
+    /// [`extend_from_slice`](Buffer::extend_from_slice) for a buffer with the given
+    /// capacity.
+    pub(crate) fn extend_from_slice_within<'b>(
+        &mut self,
+        capacity: usize,
+        other: &'b [u8],
+    ) -> Result<(), &'b [u8]> {
+        let remaining_capacity = capacity - self.len;
         let required_capacity = other.len();
         let added_len = remaining_capacity.min(required_capacity);
-        let old_len = self.len();
-        let new_len = self.len() + added_len;
-        let slice = &mut self.slice_mut()[old_len..new_len];
+        let old_len = self.len;
+        let new_len = self.len + added_len;
+        let slice = &mut self.slice_within(capacity)[old_len..new_len];
         slice.clone_from_slice(&other[..added_len]);
         self.len = new_len;
         if remaining_capacity >= required_capacity {
@@ -173,29 +352,308 @@ impl Buffer {
     }
 }
 
-impl Drop for Buffer {
-    fn drop(&mut self) {
-        let mut linked = unsafe { (*self.pool.get()).linked.load(Ordering::Acquire) };
-
-        loop {
-            let slice = &mut self.slice_mut()[..size_of::<usize>()];
-            slice.clone_from_slice(&linked.to_le_bytes());
-
-            let new_linked = self.data;
-
-            match unsafe {
-                (*self.pool.get()).linked.compare_exchange(
-                    linked,
-                    new_linked,
-                    Ordering::Release,
-                    Ordering::Acquire,
-                )
-            } {
-                Ok(_) => break,
-                Err(new_linked) => linked = new_linked,
+impl ScopedBuffer<'_> {
+    /// Insert a byte at position `index`, shifting all bytes after it to the right. If
+    /// this would exceed the capacity of the buffer, an error is returned containing the
+    /// byte that could not be written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, byte: u8) -> Result<(), u8> {
+        assert!(index <= self.len, "insertion index out of bounds");
+
+        if self.len == self.capacity() {
+            return Err(byte);
+        }
+
+        let len = self.len;
+        let slice = self.slice_mut();
+        slice.copy_within(index..len, index + 1);
+        slice[index] = byte;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove and return the byte at position `index`, shifting all bytes after it to
+    /// the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    pub fn remove(&mut self, index: usize) -> u8 {
+        assert!(index < self.len, "removal index out of bounds");
+
+        let len = self.len;
+        let byte = self[index];
+        self.copy_within(index + 1..len, index);
+        self.len -= 1;
+        byte
+    }
+
+    /// Remove and return the byte at position `index`, replacing it with the last byte
+    /// of the buffer. This does not preserve ordering, but doesn't shift any bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    pub fn swap_remove(&mut self, index: usize) -> u8 {
+        assert!(index < self.len, "swap_remove index out of bounds");
+
+        let byte = self[index];
+        self[index] = self[self.len - 1];
+        self.len -= 1;
+        byte
+    }
+
+    /// Shorten the buffer to `len` bytes. Has no effect if `len` is greater than the
+    /// length of the buffer.
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    /// Remove all bytes from the buffer.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Remove the given range from the buffer, returning its bytes as an iterator. The
+    /// bytes after the range are shifted to the left when the iterator is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn drain(&mut self, range: impl RangeBounds<usize>) -> Drain<'_> {
+        let (start, end) = bounds(range, self.len);
+        // The contents don't overlap the length of the buffer.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(self.ptr, self.len) };
+        Drain::new(bytes, &mut self.len, start, end)
+    }
+
+    /// Retain only the bytes for which `f` returns `true`, preserving their order.
+    pub fn retain(&mut self, mut f: impl FnMut(&u8) -> bool) {
+        let mut kept = 0;
+        for index in 0..self.len {
+            let byte = self[index];
+            if f(&byte) {
+                self[kept] = byte;
+                kept += 1;
             }
         }
+        self.len = kept;
+    }
+
+    /// Remove consecutive repeated bytes from the buffer.
+    pub fn dedup(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
+        let mut kept = 1;
+        for index in 1..self.len {
+            if self[index] != self[kept - 1] {
+                self[kept] = self[index];
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+
+    /// Replace the given range of the buffer with the bytes of `replace_with`. If this
+    /// would exceed the capacity of the buffer, as many bytes as fit are inserted and an
+    /// error will be returned containing a slice of the bytes that could not be written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn splice<'b>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        replace_with: &'b [u8],
+    ) -> Result<(), &'b [u8]> {
+        let (start, end) = bounds(range, self.len);
+        let len = self.len;
+        let tail = len - end;
+        let added = replace_with.len().min(self.capacity() - start - tail);
+        let slice = self.slice_mut();
+        slice.copy_within(end..len, start + added);
+        slice[start..start + added].copy_from_slice(&replace_with[..added]);
+        self.len = start + added + tail;
+        if added == replace_with.len() {
+            Ok(())
+        } else {
+            Err(&replace_with[added..])
+        }
+    }
+
+    /// Append a copy of the given range of the buffer to its end. Returns an error with
+    /// the number of bytes that could not be written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn extend_from_within(&mut self, range: impl RangeBounds<usize>) -> Result<(), usize> {
+        let (start, end) = bounds(range, self.len);
+        let len = self.len;
+        let added = (end - start).min(self.remaining());
+        self.slice_mut().copy_within(start..start + added, len);
+        self.len += added;
+        if added == end - start {
+            Ok(())
+        } else {
+            Err(end - start - added)
+        }
+    }
+
+    /// Append the bytes of the iterator to the buffer. If this would exceed the capacity
+    /// of the buffer, an error is returned containing the first byte that could not be
+    /// written, and the rest of the iterator is not consumed.
+    pub fn try_extend(&mut self, iter: impl IntoIterator<Item = u8>) -> Result<(), u8> {
+        for byte in iter {
+            self.push(byte)?;
+        }
+        Ok(())
     }
 }
 
-unsafe impl Send for Buffer {}
+impl<'a> ScopedBuffer<'a> {
+    /// Copy the contents into a new buffer from the same pool. Returns `None` if there
+    /// are no available buffers.
+    pub fn try_clone(&self) -> Option<ScopedBuffer<'a>> {
+        // The slice pointer is derived from a pointer to the backing array of the pool.
+        let base = unsafe { self.ptr.sub(self.data) };
+        let data = unsafe { (*self.pool.get()).take(base) };
+
+        unsafe { (*self.pool.get()).record_get(data.is_some()) };
+
+        let data = data?;
+        let mut buffer = unsafe { ScopedBuffer::new(base.add(data), data, self.pool) };
+        buffer.slice_mut()[..self.len].copy_from_slice(self);
+        buffer.len = self.len;
+        Some(buffer)
+    }
+
+    /// Copy the contents into a new buffer from `pool`. Returns `None` if there are no
+    /// available buffers, or the buffers of `pool` are too small to hold the contents.
+    pub fn clone_into(&self, pool: &'static Pool) -> Option<Buffer> {
+        if pool.capacity() < self.len {
+            return None;
+        }
+
+        let mut buffer = pool.get()?;
+        buffer.slice_mut()[..self.len].copy_from_slice(self);
+        buffer.len = self.len;
+        Some(buffer)
+    }
+}
+
+impl ScopedBuffer<'_> {
+    /// Move the contents into a new buffer from `pool`, releasing this buffer. Returns
+    /// this buffer as an error if there are no available buffers in `pool`, or its
+    /// buffers are too small to hold the contents.
+    pub fn grow_into(self, pool: &'static Pool) -> Result<Buffer, Self> {
+        self.clone_into(pool).ok_or(self)
+    }
+}
+
+impl Buffer {
+    /// Convert into an opaque token that holds the slot of the buffer, without returning
+    /// it to the pool.
+    pub fn into_raw(self) -> RawBuffer {
+        let buffer = ManuallyDrop::new(self);
+        RawBuffer {
+            ptr: buffer.ptr,
+            data: buffer.data,
+            len: buffer.len,
+            pool: buffer.pool,
+        }
+    }
+
+    /// Convert a token created by [`into_raw`](Buffer::into_raw) back into the buffer.
+    pub fn from_raw(raw: RawBuffer) -> Buffer {
+        Buffer {
+            ptr: raw.ptr,
+            data: raw.data,
+            len: raw.len,
+            pool: raw.pool,
+        }
+    }
+
+    /// Lend out the data with a static lifetime. The buffer is given back by
+    /// [`StaticLease::release`] in exchange for the returned borrow.
+    pub fn lease(self) -> (StaticLease, &'static mut [u8]) {
+        StaticLease::new(self)
+    }
+}
+
+impl Buffer {
+    /// Convert into an immutable buffer that can be cheaply cloned. The buffer is
+    /// returned to the pool when the last clone is dropped.
+    pub fn freeze(self) -> SharedBuffer {
+        SharedBuffer::from(self)
+    }
+}
+
+impl core::fmt::Write for ScopedBuffer<'_> {
+    /// Append the string slice to the buffer. If this would exceed the capacity of the
+    /// buffer, as many bytes as fit are written and an error is returned.
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.extend_from_slice(s.as_bytes())
+            .map_err(|_| core::fmt::Error)
+    }
+}
+
+impl Extend<u8> for ScopedBuffer<'_> {
+    /// Append the bytes of the iterator to the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bytes exceed the capacity of the buffer, use
+    /// [`try_extend`](Buffer::try_extend) to handle this instead.
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        assert!(self.try_extend(iter).is_ok(), "buffer capacity exceeded");
+    }
+}
+
+impl<'b> Extend<&'b u8> for ScopedBuffer<'_> {
+    /// Append the bytes of the iterator to the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bytes exceed the capacity of the buffer, use
+    /// [`try_extend`](Buffer::try_extend) to handle this instead.
+    fn extend<I: IntoIterator<Item = &'b u8>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl Drop for ScopedBuffer<'_> {
+    fn drop(&mut self) {
+        unsafe { (*self.pool.get()).release(self.ptr, self.data) };
+    }
+}
+
+unsafe impl Send for ScopedBuffer<'_> {}
+
+/// Convert a range into start and end indices, checking them against `len`.
+///
+/// # Panics
+///
+/// Panics if the range is out of bounds.
+pub(crate) fn bounds(range: impl RangeBounds<usize>, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).expect("range start overflows"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).expect("range end overflows"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    assert!(start <= end, "range start must not be greater than end");
+    assert!(end <= len, "range end out of bounds");
+
+    (start, end)
+}
@@ -0,0 +1,35 @@
+use ::bytes::buf::UninitSlice;
+use ::bytes::{Buf, BufMut};
+
+use crate::{BufferReader, ScopedBuffer};
+
+unsafe impl BufMut for ScopedBuffer<'_> {
+    fn remaining_mut(&self) -> usize {
+        self.remaining()
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        assert!(cnt <= self.remaining(), "cannot advance past capacity");
+        self.len += cnt;
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        let len = self.len;
+        UninitSlice::new(&mut self.slice_mut()[len..])
+    }
+}
+
+impl Buf for BufferReader<'_> {
+    fn remaining(&self) -> usize {
+        self.remaining_slice().len()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.remaining_slice()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(cnt <= Buf::remaining(self), "cannot advance past the end");
+        self.skip(cnt);
+    }
+}
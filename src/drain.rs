@@ -0,0 +1,84 @@
+/// An iterator over the bytes removed from a buffer by
+/// [`ScopedBuffer::drain`](crate::ScopedBuffer::drain).
+///
+/// The bytes after the drained range are shifted to the left when the iterator is dropped,
+/// whether or not all bytes have been iterated.
+pub struct Drain<'a> {
+    /// The contents of the buffer that is being drained.
+    bytes: &'a mut [u8],
+    /// The length of the buffer that is being drained.
+    len: &'a mut usize,
+    /// The start of the drained range.
+    start: usize,
+    /// The end of the drained range.
+    end: usize,
+    /// The index of the next byte to return from the front.
+    front: usize,
+    /// The index after the next byte to return from the back.
+    back: usize,
+}
+
+impl core::fmt::Debug for Drain<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl<'a> Drain<'a> {
+    /// Create an iterator draining `[start, end)` from the contents of a buffer.
+    pub(crate) fn new(bytes: &'a mut [u8], len: &'a mut usize, start: usize, end: usize) -> Self {
+        Self {
+            bytes,
+            len,
+            start,
+            end,
+            front: start,
+            back: end,
+        }
+    }
+
+    /// Returns the bytes that have not been iterated yet.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes[self.front..self.back]
+    }
+}
+
+impl Iterator for Drain<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.front < self.back {
+            let byte = self.bytes[self.front];
+            self.front += 1;
+            Some(byte)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Drain<'_> {
+    fn next_back(&mut self) -> Option<u8> {
+        if self.front < self.back {
+            self.back -= 1;
+            Some(self.bytes[self.back])
+        } else {
+            None
+        }
+    }
+}
+
+impl ExactSizeIterator for Drain<'_> {}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        let len = self.bytes.len();
+        self.bytes.copy_within(self.end..len, self.start);
+        *self.len = len - (self.end - self.start);
+    }
+}
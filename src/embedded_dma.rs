@@ -0,0 +1,26 @@
+use ::embedded_dma::{ReadBuffer, WriteBuffer};
+
+use crate::Buffer;
+
+// The slot of a buffer is never moved or freed while the buffer exists, so the address
+// stays stable for the duration of a transfer.
+
+unsafe impl ReadBuffer for Buffer {
+    type Word = u8;
+
+    /// Provide the contents of the buffer, `[0, len)`, for a DMA transfer to read from.
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.ptr.cast_const(), self.len)
+    }
+}
+
+unsafe impl WriteBuffer for Buffer {
+    type Word = u8;
+
+    /// Provide the full capacity of the buffer for a DMA transfer to write to. The length
+    /// of the buffer is not changed, set it with [`set_len`](Buffer::set_len) once the
+    /// transfer has completed.
+    unsafe fn write_buffer(&mut self) -> (*mut u8, usize) {
+        (self.ptr, self.capacity())
+    }
+}
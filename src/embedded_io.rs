@@ -0,0 +1,79 @@
+use core::convert::Infallible;
+
+use ::embedded_io::{BufRead, ErrorKind, ErrorType, Read, Write};
+
+use crate::{BufferReader, ScopedBuffer};
+
+impl ErrorType for ScopedBuffer<'_> {
+    type Error = ErrorKind;
+}
+
+impl Write for ScopedBuffer<'_> {
+    /// Append as many bytes as fit in the buffer. Fails with [`ErrorKind::WriteZero`] if
+    /// the buffer is full.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if !buf.is_empty() && self.remaining() == 0 {
+            return Err(ErrorKind::WriteZero);
+        }
+
+        let len = buf.len().min(self.remaining());
+        let _ = self.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ErrorType for BufferReader<'_> {
+    type Error = Infallible;
+}
+
+impl Read for BufferReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = buf.len().min(self.remaining_slice().len());
+        buf[..len].copy_from_slice(&self.remaining_slice()[..len]);
+        self.skip(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for BufferReader<'_> {
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(self.remaining_slice())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.skip(amt);
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl ::embedded_io_async::Write for ScopedBuffer<'_> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Write::write(self, buf)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl ::embedded_io_async::Read for BufferReader<'_> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Read::read(self, buf)
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl ::embedded_io_async::BufRead for BufferReader<'_> {
+    async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(self.remaining_slice())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.skip(amt);
+    }
+}
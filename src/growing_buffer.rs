@@ -0,0 +1,202 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{Pool, ScopedBuffer};
+
+/// A buffer that moves its contents to a larger pool when it runs out of capacity.
+///
+/// The pools are ordered by ascending capacity, like the pools of a
+/// [`PoolGroup`](crate::PoolGroup). When a write doesn't fit, the contents are moved to
+/// the smallest larger pool that can hold the write and the smaller buffer is released.
+///
+/// ```
+/// # use lebuf::{GrowingBuffer, PoolGroup, pools};
+/// static POOLS: PoolGroup<3> = pools![[[u8; 64]; 16], [[u8; 512]; 8], [[u8; 2048]; 2]];
+///
+/// let mut buffer = GrowingBuffer::new(POOLS.pools()).unwrap();
+/// assert_eq!(buffer.capacity(), 64);
+///
+/// buffer.extend_from_slice(&[0x00; 100]).unwrap();
+/// assert_eq!(buffer.capacity(), 512);
+/// ```
+///
+/// Pools that are declared separately are given as a list of references.
+///
+/// ```
+/// # use lebuf::{GrowingBuffer, Pool, pool};
+/// static SMALL: Pool = pool![[u8; 64]; 16];
+/// static LARGE: Pool = pool![[u8; 512]; 8];
+/// static POOLS: [&Pool; 2] = [&SMALL, &LARGE];
+///
+/// let mut buffer = GrowingBuffer::with_pools(&POOLS).unwrap();
+/// buffer.extend_from_slice(&[0x00; 100]).unwrap();
+/// assert_eq!(buffer.capacity(), 512);
+/// ```
+pub struct GrowingBuffer<'a> {
+    /// The buffer that currently holds the contents.
+    buffer: ScopedBuffer<'a>,
+    /// The pools to grow into, ordered by ascending capacity.
+    pools: Pools,
+}
+
+/// The pools a growing buffer grows into.
+#[derive(Clone, Copy)]
+enum Pools {
+    /// The pools of a pool group.
+    Group(&'static [Pool]),
+    /// Pools that are declared separately.
+    List(&'static [&'static Pool]),
+}
+
+impl Pools {
+    /// Iterate over the pools, ordered by ascending capacity.
+    fn iter(self) -> impl DoubleEndedIterator<Item = &'static Pool> + Clone {
+        let (group, list): (&[Pool], &[&Pool]) = match self {
+            Pools::Group(pools) => (pools, &[]),
+            Pools::List(pools) => (&[], pools),
+        };
+        group.iter().chain(list.iter().copied())
+    }
+}
+
+impl core::fmt::Debug for GrowingBuffer<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.buffer, f)
+    }
+}
+
+impl Deref for GrowingBuffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl DerefMut for GrowingBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer
+    }
+}
+
+impl<'a> GrowingBuffer<'a> {
+    /// Create a new buffer from the smallest pool that has an available buffer. Returns
+    /// `None` if there are no available buffers.
+    pub fn new(pools: &'static [Pool]) -> Option<Self> {
+        Self::new_in(Pools::Group(pools))
+    }
+
+    /// Create a new buffer from the smallest of the separately declared `pools` that has
+    /// an available buffer. Returns `None` if there are no available buffers.
+    pub fn with_pools(pools: &'static [&'static Pool]) -> Option<Self> {
+        Self::new_in(Pools::List(pools))
+    }
+
+    /// Create a new buffer from the smallest of `pools` that has an available buffer.
+    fn new_in(pools: Pools) -> Option<Self> {
+        let buffer = pools.iter().find_map(|pool| pool.get())?;
+        Some(Self { buffer, pools })
+    }
+
+    /// Create a new buffer that starts out with `buffer` and grows into `pools`.
+    pub fn from_buffer(buffer: ScopedBuffer<'a>, pools: &'static [Pool]) -> Self {
+        Self {
+            buffer,
+            pools: Pools::Group(pools),
+        }
+    }
+
+    /// Create a new buffer that starts out with `buffer` and grows into the separately
+    /// declared `pools`.
+    pub fn from_buffer_with_pools(
+        buffer: ScopedBuffer<'a>,
+        pools: &'static [&'static Pool],
+    ) -> Self {
+        Self {
+            buffer,
+            pools: Pools::List(pools),
+        }
+    }
+
+    /// Returns the capacity of the current buffer.
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    /// Returns the length of the buffer.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns `true` if the buffer is empty, i.e. its len is 0.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Move the contents to a larger buffer, preferring the smallest pool that can hold
+    /// `required` bytes and falling back to the largest available one. Returns `false`
+    /// if there are no available larger buffers.
+    fn grow(&mut self, required: usize) -> bool {
+        let capacity = self.buffer.capacity();
+        let larger = self.pools.iter().filter(|pool| pool.capacity() > capacity);
+
+        let buffer = larger
+            .clone()
+            .filter(|pool| pool.capacity() >= required)
+            .find_map(|pool| self.buffer.clone_into(pool))
+            .or_else(|| larger.rev().find_map(|pool| self.buffer.clone_into(pool)));
+
+        match buffer {
+            Some(buffer) => {
+                self.buffer = buffer;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Push a single byte to the end of the buffer, growing it if needed. If there is
+    /// no larger buffer available, an error is returned containing the byte that could
+    /// not be written.
+    pub fn push(&mut self, byte: u8) -> Result<(), u8> {
+        if self.buffer.remaining() == 0 {
+            self.grow(self.buffer.len() + 1);
+        }
+        self.buffer.push(byte)
+    }
+
+    /// Append the slice to the buffer, growing it if needed. If there is no buffer
+    /// available that is large enough, as many bytes as fit are written and an error will
+    /// be returned containing a slice of the bytes that could not be written.
+    pub fn extend_from_slice<'b>(&mut self, other: &'b [u8]) -> Result<(), &'b [u8]> {
+        if other.len() > self.buffer.remaining() {
+            self.grow(self.buffer.len() + other.len());
+        }
+        self.buffer.extend_from_slice(other)
+    }
+
+    /// Get the current buffer.
+    pub fn get_ref(&self) -> &ScopedBuffer<'a> {
+        &self.buffer
+    }
+
+    /// Convert into the current buffer.
+    pub fn into_buffer(self) -> ScopedBuffer<'a> {
+        self.buffer
+    }
+}
+
+impl<'a> From<GrowingBuffer<'a>> for ScopedBuffer<'a> {
+    fn from(buffer: GrowingBuffer<'a>) -> Self {
+        buffer.into_buffer()
+    }
+}
+
+impl core::fmt::Write for GrowingBuffer<'_> {
+    /// Append the string slice to the buffer, growing it if needed. If there is no buffer
+    /// available that is large enough, as many bytes as fit are written and an error is
+    /// returned.
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.extend_from_slice(s.as_bytes())
+            .map_err(|_| core::fmt::Error)
+    }
+}
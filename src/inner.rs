@@ -1,17 +1,323 @@
-use core::sync::atomic::AtomicUsize;
+use core::mem::{align_of, size_of};
+#[cfg(any(feature = "async", feature = "std"))]
+use core::sync::atomic::fence;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of bits of the linked list head that hold the index of the first free buffer.
+/// The remaining upper bits hold a tag that is incremented on every update of the head.
+const INDEX_BITS: u32 = usize::BITS / 2;
+
+/// Mask to extract the buffer index from the linked list head.
+const INDEX_MASK: usize = (1 << INDEX_BITS) - 1;
+
+/// Buffer index that marks the end of the linked list of free buffers.
+const END: usize = INDEX_MASK;
+
+#[cfg(test)]
+std::thread_local! {
+    /// Called once by `pop` on this thread, between reading the next index and swapping
+    /// the head, to interleave other operations deterministically.
+    pub(crate) static POP_HOOK: core::cell::Cell<Option<std::boxed::Box<dyn FnOnce()>>> =
+        const { core::cell::Cell::new(None) };
+    /// Whether updates of the head on this thread leave the tag unchanged, to reproduce
+    /// the ABA problem of an untagged head.
+    pub(crate) static UNTAGGED: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+}
 
 /// Inner data structure that is referenced by the buffers.
 pub(crate) struct Inner {
-    /// Method to get a raw pointer to a backing slice for a given index.
-    pub(crate) get_ptr: fn(usize) -> *mut u8,
     /// The length of the slice backing the buffer.
     pub(crate) backing_len: usize,
     /// The capacity of a single buffer.
     pub(crate) capacity: usize,
-    /// The index of the first buffer that is part of the linked list.
+    /// The distance between the starts of two consecutive buffers, at least the capacity.
+    pub(crate) stride: usize,
+    /// Whether the storage was initialized before the pool was created. If not, buffers
+    /// are initialized when they are handed out for the first time.
+    pub(crate) zeroed: bool,
+    /// The tagged index of the first buffer that is part of the linked list.
     pub(crate) linked: AtomicUsize,
     /// The index of the first buffer that is still unlinked.
     pub(crate) unlinked: AtomicUsize,
+    /// Usage statistics of the pool.
+    #[cfg(feature = "stats")]
+    pub(crate) stats: crate::Stats,
+    /// Wakers of the tasks that are waiting for a buffer to be released.
+    #[cfg(feature = "async")]
+    pub(crate) wakers: crate::WakerList,
+    /// Threads that are waiting for a buffer to be released.
+    #[cfg(feature = "std")]
+    pub(crate) parking: crate::Parking,
+}
+
+impl Inner {
+    /// Create the inner data structure of a pool with the given geometry.
+    pub(crate) const fn new(
+        backing_len: usize,
+        capacity: usize,
+        stride: usize,
+        zeroed: bool,
+    ) -> Self {
+        assert!(capacity >= size_of::<usize>());
+        assert!(capacity <= stride);
+        assert!(Self::fits(backing_len, stride));
+
+        Self {
+            backing_len,
+            capacity,
+            stride,
+            zeroed,
+            linked: AtomicUsize::new(Self::pack(END, 0)),
+            unlinked: AtomicUsize::new(0),
+            #[cfg(feature = "stats")]
+            stats: crate::Stats::new(),
+            #[cfg(feature = "async")]
+            wakers: crate::WakerList::new(),
+            #[cfg(feature = "std")]
+            parking: crate::Parking::new(),
+        }
+    }
+
+    /// Create the inner data structure of a pool over `storage` with buffers of
+    /// `capacity` bytes, and return it with a pointer to the start of the backing array.
+    /// The metadata of the pool is carved out of `storage` as well, as many buffers as
+    /// fit in the rest of it are used.
+    pub(crate) fn from_slice(storage: &mut [u8], capacity: usize) -> (*mut u8, Self) {
+        // The metadata is located relative to the start of the backing array, so the
+        // start has to be aligned for the reference counts.
+        let skip = storage
+            .as_ptr()
+            .align_offset(align_of::<AtomicUsize>())
+            .min(storage.len());
+        let storage = &mut storage[skip..];
+
+        // The last index marks the end of the linked list, so it can't be a buffer.
+        let mut count =
+            (storage.len() / capacity.saturating_add(size_of::<AtomicUsize>())).min(END - 1);
+        while Self::refs_offset(count * capacity) + count * size_of::<AtomicUsize>() > storage.len()
+        {
+            count -= 1;
+        }
+
+        // Any bytes are valid buffer contents and reference counts, so the storage is
+        // already initialized.
+        let inner = Self::new(count * capacity, capacity, capacity, true);
+        (storage.as_mut_ptr(), inner)
+    }
+
+    /// Pack a buffer index and a tag into a linked list head.
+    const fn pack(index: usize, tag: usize) -> usize {
+        (tag << INDEX_BITS) | (index & INDEX_MASK)
+    }
+
+    /// Get the buffer index of a linked list head.
+    const fn index(head: usize) -> usize {
+        head & INDEX_MASK
+    }
+
+    /// Get the tag of a linked list head.
+    const fn tag(head: usize) -> usize {
+        head >> INDEX_BITS
+    }
+
+    /// Get the tag for the next update of a linked list head.
+    fn next_tag(head: usize) -> usize {
+        #[cfg(test)]
+        if UNTAGGED.get() {
+            return Self::tag(head);
+        }
+
+        Self::tag(head).wrapping_add(1)
+    }
+
+    /// Check whether a pool with the given geometry can be tracked by the linked list.
+    const fn fits(backing_len: usize, stride: usize) -> bool {
+        backing_len / stride < END
+    }
+
+    /// Get the offset of the reference counts from the start of the backing array. The
+    /// reference counts directly follow the backing array, aligned for `AtomicUsize`.
+    pub(crate) const fn refs_offset(backing_len: usize) -> usize {
+        backing_len.next_multiple_of(align_of::<AtomicUsize>())
+    }
+
+    /// Get the reference count of the buffer at `ptr` with the given data index.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the slice with the given data index of this pool, and must
+    /// be derived from a pointer to the complete storage of the pool.
+    pub(crate) unsafe fn refs(&self, ptr: *mut u8, data: usize) -> &AtomicUsize {
+        let base = ptr.sub(data);
+        let refs = base.add(Self::refs_offset(self.backing_len)) as *const AtomicUsize;
+        &*refs.add(data / self.stride)
+    }
+
+    /// Get the number of buffers in the pool.
+    pub(crate) const fn count(&self) -> usize {
+        self.backing_len / self.stride
+    }
+
+    /// Take a buffer from the pool backed by `base` and return its data index. Returns
+    /// `None` if there are no available buffers. This doesn't count as a call to get a
+    /// buffer in the usage statistics, so retries of a single call are counted once.
+    pub(crate) fn take(&self, base: *mut u8) -> Option<usize> {
+        let data = self.take_unlinked(base).or_else(|| self.pop(base));
+
+        #[cfg(feature = "stats")]
+        if data.is_some() {
+            self.stats.record_take();
+        }
+
+        data
+    }
+
+    /// Record a call to get a buffer in the usage statistics, if they are enabled.
+    #[cfg_attr(not(feature = "stats"), allow(unused_variables))]
+    pub(crate) fn record_get(&self, success: bool) {
+        #[cfg(feature = "stats")]
+        self.stats.record_get(success);
+    }
+
+    /// Take a buffer of the pool backed by `base` that has never been used before.
+    /// Returns `None` if all buffers are part of the linked list of free buffers or in use.
+    fn take_unlinked(&self, base: *mut u8) -> Option<usize> {
+        // Get the unlinked data index. This can be done with `Relaxed` memory ordering
+        // because there are no other changes that we need to acquire.
+        let mut unlinked = self.unlinked.load(Ordering::Relaxed);
+
+        // Check if the unlinked index is smaller than the length of the backing array.
+        while unlinked < self.backing_len {
+            // Calculate the next unlinked index.
+            let next_unlinked = unlinked + self.stride;
+
+            // Swap the unlinked index with next unlinked index. This can be done with
+            // `Relaxed` memory ordering because there are no other changes we need
+            // to release or acquire.
+            match self.unlinked.compare_exchange(
+                unlinked,
+                next_unlinked,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                // The swap succeeded so we hand out the buffer.
+                Ok(data) => {
+                    if !self.zeroed {
+                        unsafe { self.init(base, data) };
+                    }
+                    return Some(data);
+                }
+                // The swap failed so we get the next unlinked index and try again.
+                Err(next_unlinked) => unlinked = next_unlinked,
+            }
+        }
+
+        // The unlinked index is greater than the backing array, so all
+        // buffers are now part of the linked list of free buffers.
+        None
+    }
+
+    /// Initialize the buffer with the given data index and its reference count, in
+    /// storage that was not initialized before the pool was created.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must not be in use.
+    unsafe fn init(&self, base: *mut u8, data: usize) {
+        base.add(data).write_bytes(0x00, self.stride);
+
+        let refs = base.add(Self::refs_offset(self.backing_len)) as *mut AtomicUsize;
+        refs.add(data / self.stride).write(AtomicUsize::new(0));
+    }
+
+    /// Pop the first buffer from the linked list of free buffers of the pool backed by
+    /// `base` and return its data index. Returns `None` if the linked list is empty.
+    fn pop(&self, base: *mut u8) -> Option<usize> {
+        // Get the linked head. This is done with `Acquire` memory ordering because we
+        // need to make sure the next index contained inside the slice is correct.
+        let mut linked = self.linked.load(Ordering::Acquire);
+
+        loop {
+            let index = Self::index(linked);
+
+            // Check if the linked list is empty.
+            if index == END {
+                return None;
+            }
+
+            let data = index * self.stride;
+
+            // Get the index of the next linked buffer. If another thread pops this buffer
+            // in the meantime the value might be garbage, but then the tag will have changed
+            // and the swap below fails.
+            let next = unsafe { (base.add(data) as *const usize).read_unaligned() };
+
+            #[cfg(test)]
+            if let Some(hook) = POP_HOOK.take() {
+                hook();
+            }
+
+            // Replace the head with the next index, bumping the tag. Because the tag changes
+            // on every update, the swap fails if the buffer was popped and pushed back by
+            // another thread in the meantime (the ABA problem). In case this swap fails
+            // we'll acquire all other changes because we'll need to get a new next index.
+            match self.linked.compare_exchange(
+                linked,
+                Self::pack(next, Self::next_tag(linked)),
+                Ordering::Relaxed,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(data),
+                Err(new_linked) => linked = new_linked,
+            }
+        }
+    }
+
+    /// Push the buffer at `ptr` with the given data index onto the linked list of free
+    /// buffers.
+    fn push(&self, ptr: *mut u8, data: usize) {
+        let index = data / self.stride;
+        let mut linked = self.linked.load(Ordering::Relaxed);
+
+        loop {
+            // Store the index of the current first buffer inside the buffer being freed.
+            unsafe { (ptr as *mut usize).write_unaligned(Self::index(linked)) };
+
+            // Make the freed buffer the new head. This is done with `Release` memory ordering
+            // so the next index written above is visible to the thread that pops it.
+            match self.linked.compare_exchange(
+                linked,
+                Self::pack(index, Self::next_tag(linked)),
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(new_linked) => linked = new_linked,
+            }
+        }
+    }
+
+    /// Release the buffer at `ptr` with the given data index back to the pool and
+    /// notify anyone waiting for a buffer.
+    pub(crate) fn release(&self, ptr: *mut u8, data: usize) {
+        // Record the release before the buffer can be taken again, so the number of
+        // buffers in use never exceeds the number of buffers.
+        #[cfg(feature = "stats")]
+        self.stats.record_release();
+
+        self.push(ptr, data);
+
+        // Make sure the push is visible before checking for waiting tasks, a task
+        // registers itself before it tries to get a buffer one last time.
+        #[cfg(any(feature = "async", feature = "std"))]
+        fence(Ordering::SeqCst);
+
+        #[cfg(feature = "async")]
+        self.wakers.wake();
+
+        #[cfg(feature = "std")]
+        self.parking.unpark();
+    }
 }
 
 unsafe impl Sync for Inner {}
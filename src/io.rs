@@ -0,0 +1,78 @@
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+
+use crate::{BufferReader, ScopedBuffer};
+
+impl Write for ScopedBuffer<'_> {
+    /// Append as many bytes as fit in the buffer, like
+    /// [`extend_from_slice`](ScopedBuffer::extend_from_slice). Returns `Ok(0)` if the buffer
+    /// is full.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let remaining = match self.extend_from_slice(buf) {
+            Ok(()) => &[],
+            Err(remaining) => remaining,
+        };
+        Ok(buf.len() - remaining.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl ScopedBuffer<'_> {
+    /// Read from `reader` directly into the remaining space of the buffer with a single
+    /// call to [`Read::read`]. Returns the number of bytes that were read, which is `0`
+    /// if the buffer is full or the reader reached its end.
+    pub fn read_from(&mut self, reader: &mut impl Read) -> io::Result<usize> {
+        if self.remaining() == 0 {
+            return Ok(0);
+        }
+
+        let len = self.len;
+        let read = reader.read(&mut self.slice_mut()[len..])?;
+        self.len += read.min(self.remaining());
+        Ok(read)
+    }
+}
+
+impl Read for BufferReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.remaining_slice().len());
+        buf[..len].copy_from_slice(&self.remaining_slice()[..len]);
+        self.skip(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for BufferReader<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.remaining_slice())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.skip(amt);
+    }
+}
+
+impl Seek for BufferReader<'_> {
+    /// Seek to a position in the buffer. Positions past the end of the buffer are clamped
+    /// to its length.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => (self.get_ref().len() as u64).checked_add_signed(offset),
+            SeekFrom::Current(offset) => (self.position() as u64).checked_add_signed(offset),
+        };
+
+        match pos {
+            Some(pos) => {
+                self.set_position(usize::try_from(pos).unwrap_or(usize::MAX));
+                Ok(self.position() as u64)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
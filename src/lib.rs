@@ -1,4 +1,4 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 
 //! Lockless and statically allocated byte buffers
 //!
@@ -23,34 +23,94 @@
 // # Implementation
 //
 // The buffer pool is backed by a contiguous slice of bytes. When a buffer is not in use
-// the first few bytes are used to point to the next buffer that is not use, creating a
-// singly linked list of free buffers. The last buffer in the chain will point to a buffer
-// outside of the slice.
+// the first few bytes are used to store the index of the next buffer that is not in use,
+// creating a singly linked list of free buffers. The last buffer in the chain holds an
+// index that marks the end of the list.
 //
 // ```text
 // ╔═══════════════════╗───────────────────────────────────────┐
-// ║ 18   00   00   00 ║ 00   00   00   00   00   00   00   00 │
+// ║ 02   00   00   00 ║ 00   00   00   00   00   00   00   00 │
 // ╚═══════════════════╝───────────────────────────────────────┤
 // │ 3F   43   12   32   48   A3   2D   11   26   B4   23   00 │
 // ╔═══════════════════╗───────────────────────────────────────┤
-// ║ 24   00   00   00 ║ 00   00   00   00   00   00   00   00 │
+// ║ 03   00   00   00 ║ 00   00   00   00   00   00   00   00 │
 // ╠═══════════════════╣───────────────────────────────────────┤
-// ║ 3C   00   00   00 ║ 00   00   00   00   00   00   00   00 │
+// ║ FF   FF   00   00 ║ 00   00   00   00   00   00   00   00 │
 // ╚═══════════════════╝───────────────────────────────────────┤
 // │ 8A   48   A3   9D   2D   11   26   4F   B4   23   00   99 │
 // └───────────────────────────────────────────────────────────┘
 // ```
+//
+// The backing slice is followed by the metadata of the pool, a reference count for every
+// buffer that is used by shared buffers to keep track of their clones. When the pool is
+// aligned, every buffer is padded to a multiple of the alignment. Storage that is not
+// initialized at startup is zeroed one buffer at a time, when it is first handed out, so
+// the memory of a buffer is always initialized.
+//
+// The head of the linked list packs the index of the first free buffer in the lower half
+// of a `usize` and a tag in the upper half. The tag is incremented on every update of the
+// head, so a thread that read a stale head can't swap it in after other threads popped and
+// pushed back the same buffer in the meantime (the ABA problem).
 
 mod buffer;
+#[cfg(feature = "bytes")]
+mod bytes;
+mod drain;
+#[cfg(feature = "embedded-dma")]
+mod embedded_dma;
+#[cfg(feature = "embedded-io")]
+mod embedded_io;
+mod growing_buffer;
 mod inner;
+#[cfg(feature = "std")]
+mod io;
+mod packet_buffer;
+#[cfg(feature = "std")]
+mod parking;
 mod pool;
+mod pool_cell;
+mod pool_group;
+mod raw_buffer;
+mod reader;
+mod scoped_pool;
+mod shared_buffer;
+mod static_buffer;
+mod static_lease;
+mod static_pool;
+#[cfg(feature = "stats")]
+mod stats;
+mod storage;
+mod str_buffer;
+#[cfg(feature = "async")]
+mod waker;
 
 pub use buffer::*;
+pub use drain::*;
+pub use growing_buffer::*;
+pub use packet_buffer::*;
 pub use pool::*;
+pub use pool_cell::*;
+pub use pool_group::*;
+pub use raw_buffer::*;
+pub use reader::*;
+pub use scoped_pool::*;
+pub use shared_buffer::*;
+pub use static_buffer::*;
+pub use static_lease::*;
+pub use static_pool::*;
+#[cfg(feature = "stats")]
+pub use stats::*;
+pub use storage::*;
+pub use str_buffer::*;
 
 pub(crate) use inner::*;
+#[cfg(feature = "std")]
+pub(crate) use parking::*;
+#[cfg(feature = "async")]
+pub(crate) use waker::*;
 
 #[test]
+#[allow(clippy::redundant_pattern_matching)]
 fn pool_get() {
     static POOL: Pool = pool![[u8; 8]; 2];
 
@@ -86,6 +146,7 @@ fn pool_get() {
 }
 
 #[test]
+#[allow(clippy::redundant_pattern_matching)]
 fn buffer_extend_from_slice() {
     static POOL: Pool = pool![[u8; 8]; 2];
 
@@ -106,6 +167,7 @@ fn buffer_extend_from_slice() {
 }
 
 #[test]
+#[allow(clippy::redundant_pattern_matching)]
 fn buffer_resize() {
     static POOL: Pool = pool![[u8; 8]; 2];
 
@@ -136,6 +198,32 @@ fn buffer_resize() {
 }
 
 #[test]
+fn buffer_spare_capacity() {
+    static POOL: Pool = pool![[u8; 8]; 2];
+
+    let mut buffer = POOL.get().unwrap();
+
+    let spare = buffer.spare_capacity_mut();
+    assert_eq!(spare.len(), 8);
+    spare[0].write(0x01);
+    spare[1].write(0x02);
+    buffer.advance(2);
+
+    assert_eq!(buffer.as_ref(), &[0x01, 0x02]);
+
+    let written = buffer.fill_with(|spare| {
+        spare[..3].copy_from_slice(&[0x03, 0x04, 0x05]);
+        3
+    });
+
+    assert_eq!(written, 3);
+    assert_eq!(buffer.as_ref(), &[0x01, 0x02, 0x03, 0x04, 0x05]);
+    assert_eq!(buffer.fill_with(|spare| spare.len()), 3);
+    assert_eq!(buffer.remaining(), 0);
+}
+
+#[test]
+#[allow(clippy::redundant_pattern_matching)]
 fn buffer_push() {
     static POOL: Pool = pool![[u8; 8]; 2];
 
@@ -168,6 +256,7 @@ fn buffer_push() {
 }
 
 #[test]
+#[allow(clippy::redundant_pattern_matching)]
 fn buffer_pop() {
     static POOL: Pool = pool![[u8; 8]; 2];
 
@@ -200,6 +289,387 @@ fn buffer_pop() {
     assert_eq!(buffer.as_ref(), &[]);
 }
 
+#[test]
+fn buffer_insert_remove() {
+    static POOL: Pool = pool![[u8; 8]; 2];
+
+    let mut buffer = POOL.get().unwrap();
+
+    buffer.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]).unwrap();
+    buffer.insert(1, 0x05).unwrap();
+
+    assert_eq!(buffer.as_ref(), &[0x01, 0x05, 0x02, 0x03, 0x04]);
+    assert_eq!(buffer.remove(2), 0x02);
+    assert_eq!(buffer.swap_remove(0), 0x01);
+    assert_eq!(buffer.as_ref(), &[0x04, 0x05, 0x03]);
+
+    buffer.extend_from_slice(&[0x03, 0x03, 0x06]).unwrap();
+    buffer.dedup();
+
+    assert_eq!(buffer.as_ref(), &[0x04, 0x05, 0x03, 0x06]);
+
+    buffer.retain(|&byte| byte != 0x05);
+
+    assert_eq!(buffer.as_ref(), &[0x04, 0x03, 0x06]);
+
+    buffer.truncate(2);
+    buffer.extend_from_within(..).unwrap();
+
+    assert_eq!(buffer.as_ref(), &[0x04, 0x03, 0x04, 0x03]);
+
+    let result = buffer.extend_from_within(..);
+
+    assert!(result.is_ok());
+    assert!(matches!(buffer.insert(0, 0x07), Err(0x07)));
+
+    buffer.truncate(5);
+    buffer[4] = 0x05;
+
+    assert_eq!(buffer.extend_from_within(1..5), Err(1));
+    assert_eq!(
+        buffer.as_ref(),
+        &[0x04, 0x03, 0x04, 0x03, 0x05, 0x03, 0x04, 0x03]
+    );
+
+    buffer.clear();
+
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn buffer_drain_splice() {
+    static POOL: Pool = pool![[u8; 8]; 2];
+
+    let mut buffer = POOL.get().unwrap();
+
+    buffer.extend(&[0x01, 0x02, 0x03, 0x04, 0x05]);
+
+    let mut drain = buffer.drain(1..3);
+
+    assert_eq!(drain.next(), Some(0x02));
+    drop(drain);
+    assert_eq!(buffer.as_ref(), &[0x01, 0x04, 0x05]);
+
+    let result_1 = buffer.splice(1..2, &[0x06, 0x07]);
+
+    assert!(result_1.is_ok());
+    assert_eq!(buffer.as_ref(), &[0x01, 0x06, 0x07, 0x05]);
+
+    let result_2 = buffer.splice(..1, &[0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D]);
+
+    assert!(matches!(result_2, Err(&[0x0D])));
+    assert_eq!(
+        buffer.as_ref(),
+        &[0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x06, 0x07, 0x05]
+    );
+
+    buffer.truncate(6);
+    let result_3 = buffer.try_extend([0x0E, 0x0F, 0x10]);
+
+    assert!(matches!(result_3, Err(0x10)));
+    assert_eq!(buffer.len(), 8);
+}
+
+#[test]
+#[allow(clippy::mutable_key_type)]
+fn buffer_traits() {
+    static POOL: Pool = pool![[u8; 8]; 4];
+
+    let mut buffer_1 = POOL.get().unwrap();
+    let mut buffer_2 = POOL.get().unwrap();
+
+    buffer_1.extend_from_slice(&[0x01, 0x02]).unwrap();
+    buffer_2.extend_from_slice(&[0x01, 0x03]).unwrap();
+
+    assert_eq!(buffer_1, [0x01, 0x02]);
+    assert_eq!(buffer_1, &[0x01, 0x02][..]);
+    assert_eq!([0x01, 0x02], buffer_1);
+    assert_ne!(buffer_1, buffer_2);
+    assert!(buffer_1 < buffer_2);
+    assert_eq!((&buffer_1).into_iter().sum::<u8>(), 0x03);
+
+    let mut map = std::collections::HashMap::new();
+    map.insert(buffer_1, 1);
+    map.insert(buffer_2, 2);
+
+    assert_eq!(map.get(&[0x01, 0x03][..]), Some(&2));
+}
+
+#[test]
+fn buffer_try_clone() {
+    static POOL_1: Pool = pool![[u8; 16]; 2];
+    static POOL_2: Pool = pool![[u8; 8]; 1];
+
+    let mut buffer = POOL_1.get().unwrap();
+    buffer.extend_from_slice(&[0x01, 0x02, 0x03]).unwrap();
+
+    let clone_1 = buffer.try_clone().unwrap();
+
+    assert_eq!(clone_1, buffer);
+    assert!(buffer.try_clone().is_none());
+
+    drop(clone_1);
+    let clone_2 = buffer.clone_into(&POOL_2).unwrap();
+    drop(buffer);
+
+    assert_eq!(clone_2, [0x01, 0x02, 0x03]);
+    assert_eq!(clone_2.capacity(), 8);
+
+    let mut buffer = POOL_1.get().unwrap();
+    buffer.resize(9).unwrap();
+
+    assert!(buffer.clone_into(&POOL_2).is_none());
+}
+
+#[test]
+fn buffer_grow_into() {
+    static POOLS: PoolGroup<3> = pools![[[u8; 8]; 1], [[u8; 16]; 1], [[u8; 32]; 1]];
+
+    let mut buffer = POOLS.get_for(0).unwrap();
+    buffer.extend_from_slice(&[0x01, 0x02]).unwrap();
+
+    let buffer = buffer.grow_into(&POOLS.pools()[1]).unwrap();
+
+    assert_eq!(buffer, [0x01, 0x02]);
+    assert_eq!(buffer.capacity(), 16);
+
+    let mut growing = GrowingBuffer::from_buffer(buffer, POOLS.pools());
+    growing.extend_from_slice(&[0x03; 20]).unwrap();
+
+    assert_eq!(growing.capacity(), 32);
+    assert_eq!(growing.len(), 22);
+    assert!(POOLS.get_for(9).is_some());
+
+    let result = growing.extend_from_slice(&[0x04; 20]);
+
+    assert!(matches!(result, Err(&[0x04, 0x04, ..])));
+    assert_eq!(growing.len(), 32);
+}
+
+#[test]
+fn growing_buffer_with_pools() {
+    static SMALL: Pool = pool![[u8; 8]; 1];
+    static MEDIUM: Pool = pool![[u8; 16]; 1];
+    static LARGE: Pool = pool![[u8; 32]; 1];
+    static POOLS: [&Pool; 3] = [&SMALL, &MEDIUM, &LARGE];
+
+    let mut growing = GrowingBuffer::with_pools(&POOLS).unwrap();
+    growing.extend_from_slice(&[0x01; 20]).unwrap();
+
+    assert_eq!(growing.capacity(), 32);
+    assert!(SMALL.get().is_some());
+
+    let buffer = MEDIUM.get().unwrap();
+    let mut growing = GrowingBuffer::from_buffer_with_pools(buffer, &POOLS);
+
+    assert!(growing.extend_from_slice(&[0x02; 20]).is_err());
+    assert_eq!(growing.capacity(), 16);
+}
+
+#[test]
+fn buffer_into_raw() {
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let mut buffer = POOL.get().unwrap();
+    buffer.extend_from_slice(&[0x01, 0x02]).unwrap();
+
+    let raw = buffer.into_raw();
+
+    assert_eq!(raw.len(), 2);
+    assert!(POOL.get().is_none());
+
+    let buffer = Buffer::from_raw(raw);
+    let (lease, borrow) = buffer.lease();
+    borrow[1] = 0x03;
+
+    let buffer = lease.release(borrow);
+
+    assert_eq!(buffer, [0x01, 0x03]);
+
+    drop(buffer);
+    let (lease, _borrow) = POOL.get().unwrap().lease();
+    drop(lease);
+
+    assert!(POOL.get().is_none());
+}
+
+#[test]
+fn buffer_freeze() {
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let mut buffer = POOL.get().unwrap();
+
+    buffer.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]).unwrap();
+
+    let shared_1 = buffer.freeze();
+    let shared_2 = shared_1.clone();
+
+    assert_eq!(shared_1.as_ref(), &[0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(shared_2.as_ref(), &[0x01, 0x02, 0x03, 0x04]);
+
+    drop(shared_1);
+
+    assert!(POOL.get().is_none());
+
+    let handle = std::thread::spawn(move || shared_2.len());
+
+    assert_eq!(handle.join().unwrap(), 4);
+    assert!(POOL.get().is_some());
+}
+
+#[test]
+fn shared_buffer_split() {
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let mut buffer = POOL.get().unwrap();
+
+    buffer
+        .extend_from_slice(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08])
+        .unwrap();
+
+    let mut payload = buffer.freeze();
+    let header = payload.split_to(2);
+    let trailer = payload.split_off(4);
+    let middle = payload.slice(1..=2);
+
+    assert_eq!(header.as_ref(), &[0x01, 0x02]);
+    assert_eq!(payload.as_ref(), &[0x03, 0x04, 0x05, 0x06]);
+    assert_eq!(trailer.as_ref(), &[0x07, 0x08]);
+    assert_eq!(middle.as_ref(), &[0x04, 0x05]);
+    assert_eq!(payload.slice(..).as_ref(), payload.as_ref());
+    assert!(payload.slice(4..).is_empty());
+
+    drop(header);
+    drop(payload);
+    drop(trailer);
+
+    assert!(POOL.get().is_none());
+
+    drop(middle);
+
+    assert!(POOL.get().is_some());
+}
+
+#[test]
+fn packet_buffer_headroom() {
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let mut packet = POOL.get_packet(4).unwrap();
+
+    assert_eq!(packet.headroom(), 4);
+    assert_eq!(packet.tailroom(), 4);
+
+    let result_1 = packet.extend_from_slice(&[0x05, 0x06]);
+    let result_2 = packet.push_front(&[0x03, 0x04]);
+    let result_3 = packet.push_front(&[0x00, 0x01, 0x02]);
+
+    assert!(result_1.is_ok());
+    assert!(result_2.is_ok());
+    assert!(matches!(result_3, Err(&[0x00, 0x01, 0x02])));
+    assert_eq!(packet.as_ref(), &[0x03, 0x04, 0x05, 0x06]);
+
+    assert_eq!(packet.pull_front(1), Some(&[0x03][..]));
+    assert_eq!(packet.pull_back(1), Some(&[0x06][..]));
+    assert_eq!(packet.pull_front(3), None);
+    assert_eq!(packet.as_ref(), &[0x04, 0x05]);
+
+    let result_4 = packet.reserve_head(6);
+    let result_5 = packet.reserve_head(7);
+
+    assert!(result_4.is_ok());
+    assert!(matches!(result_5, Err(1)));
+    assert_eq!(packet.headroom(), 6);
+    assert_eq!(packet.tailroom(), 0);
+    assert_eq!(packet.as_ref(), &[0x04, 0x05]);
+
+    let buffer = packet.into_buffer();
+
+    assert_eq!(buffer.as_ref(), &[0x04, 0x05]);
+
+    let mut packet = PacketBuffer::from(buffer);
+
+    packet.reserve_head(2).unwrap();
+
+    let shared = packet.freeze();
+
+    assert_eq!(shared.as_ref(), &[0x04, 0x05]);
+}
+
+#[test]
+fn buffer_fmt_write() {
+    use core::fmt::Write;
+
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let mut buffer = POOL.get().unwrap();
+
+    let result_1 = write!(buffer, "{}+{}", 12, 34);
+    let result_2 = write!(buffer, "={}", 460);
+
+    assert!(result_1.is_ok());
+    assert!(result_2.is_err());
+    assert_eq!(buffer.as_ref(), b"12+34=46");
+}
+
+#[test]
+fn str_buffer() {
+    use core::fmt::Write;
+
+    static POOL: Pool = pool![[u8; 8]; 2];
+
+    let mut string = StrBuffer::from_utf8(POOL.get().unwrap()).unwrap();
+
+    let result_1 = string.push_str("ab");
+    let result_2 = string.push('é');
+    let result_3 = string.push_str("cdeé");
+    let result_4 = string.push('é');
+
+    assert!(result_1.is_ok());
+    assert!(result_2.is_ok());
+    assert!(matches!(result_3, Err("é")));
+    assert!(matches!(result_4, Err('é')));
+    assert_eq!(&*string, "abécde");
+    assert_eq!(string.remaining(), 1);
+
+    assert_eq!(string.pop(), Some('e'));
+
+    let euro = '€';
+    let result_5 = write!(string, "{euro}");
+
+    assert!(result_5.is_err());
+    assert_eq!(&*string, "abécd");
+
+    let mut buffer = POOL.get().unwrap();
+
+    buffer.extend_from_slice(&[0x61, 0xC3]).unwrap();
+
+    let result_6 = StrBuffer::from_utf8(buffer);
+
+    assert!(result_6.is_err());
+}
+
+#[test]
+fn buffer_reader() {
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let mut buffer = POOL.get().unwrap();
+
+    buffer.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]).unwrap();
+
+    let mut reader = BufferReader::new(buffer);
+
+    reader.skip(1);
+
+    assert_eq!(reader.position(), 1);
+    assert_eq!(reader.remaining_slice(), &[0x02, 0x03, 0x04]);
+
+    reader.set_position(10);
+
+    assert_eq!(reader.position(), 4);
+    assert_eq!(reader.remaining_slice(), &[]);
+    assert_eq!(reader.into_inner().as_ref(), &[0x01, 0x02, 0x03, 0x04]);
+}
+
 #[test]
 fn multiple_pools() {
     static POOL1: Pool = pool![[u8; 8]; 2];
@@ -220,6 +690,211 @@ fn multiple_pools() {
 }
 
 #[test]
+fn pool_align() {
+    static POOL: Pool = pool![[u8; 20]; 3, align = 32];
+
+    let mut buffer_1 = POOL.get().unwrap();
+    let buffer_2 = POOL.get().unwrap();
+    let buffer_3 = POOL.get().unwrap();
+
+    assert_eq!(buffer_1.capacity(), 20);
+    assert_eq!(buffer_1.as_ptr() as usize % 32, 0);
+    assert_eq!(buffer_2.as_ptr() as usize % 32, 0);
+    assert_eq!(buffer_3.as_ptr() as usize % 32, 0);
+
+    buffer_1.resize(20).unwrap();
+    let shared = buffer_1.freeze();
+    let clone = shared.clone();
+    drop(shared);
+    drop(buffer_2);
+
+    assert_eq!(clone.len(), 20);
+    assert!(POOL.get().is_some());
+}
+
+#[test]
+fn pool_uninit() {
+    static POOL: Pool = pool![[u8; 8]; 2, zeroed = false];
+
+    let mut buffer_1 = POOL.get().unwrap();
+
+    assert_eq!(buffer_1.fill_with(|spare| spare.len()), 8);
+    assert_eq!(buffer_1, [0x00; 8]);
+
+    let shared = buffer_1.freeze();
+    let clone = shared.clone();
+    drop(shared);
+    drop(clone);
+
+    let buffer_2 = POOL.get();
+    let buffer_3 = POOL.get();
+
+    assert!(buffer_2.is_some());
+    assert!(buffer_3.is_some());
+}
+
+#[test]
+fn pool_from_slice() {
+    static POOL: PoolCell = PoolCell::new();
+
+    assert!(POOL.get().is_none());
+
+    #[repr(align(8))]
+    struct Aligned([u8; 96]);
+
+    let storage = Box::leak(Box::new(Aligned([0x00; 96])));
+    let pool = POOL.set(&mut storage.0, 16).unwrap();
+
+    assert_eq!(pool.capacity(), 16);
+    assert_eq!(pool.count(), 4);
+    assert!(POOL.set(&mut [], 16).is_err());
+
+    let pool = POOL.get_or_init(|| unreachable!());
+    let buffers: Vec<_> = core::iter::from_fn(|| pool.get()).collect();
+
+    assert_eq!(buffers.len(), 4);
+
+    let mut shared = Vec::new();
+    for mut buffer in buffers {
+        buffer.extend_from_slice(&[0xFF; 16]).unwrap();
+        let buffer = buffer.freeze();
+        shared.push(buffer.clone());
+        shared.push(buffer);
+    }
+    drop(shared);
+
+    assert!(pool.get().is_some());
+}
+
+#[test]
+fn pool_cell_try_get_or_init() {
+    static POOL: PoolCell = PoolCell::new();
+
+    let pool = POOL.try_get_or_init(|| {
+        // Initializing the pool again while it is being initialized, like an interrupt
+        // handler would, doesn't wait for it.
+        assert!(POOL.try_get_or_init(|| unreachable!()).is_none());
+
+        (Box::leak(vec![0u8; 256].into_boxed_slice()), 16)
+    });
+
+    assert_eq!(pool.unwrap().capacity(), 16);
+    assert!(POOL.try_get_or_init(|| unreachable!()).is_some());
+}
+
+#[test]
+fn pool_cell_init_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    static POOL: PoolCell = PoolCell::new();
+
+    let result = catch_unwind(AssertUnwindSafe(|| POOL.get_or_init(|| panic!())));
+
+    assert!(result.is_err());
+    assert!(POOL.get().is_none());
+
+    let result = catch_unwind(AssertUnwindSafe(|| POOL.try_get_or_init(|| panic!())));
+
+    assert!(result.is_err());
+
+    let pool = POOL.get_or_init(|| (Box::leak(vec![0u8; 256].into_boxed_slice()), 16));
+
+    assert_eq!(pool.capacity(), 16);
+}
+
+#[test]
+fn scoped_pool_get() {
+    // Aligned storage, so no bytes are skipped to align the reference counts.
+    #[repr(align(8))]
+    struct Aligned([u8; 64]);
+
+    let mut storage = Aligned([0x00; 64]);
+    let pool = ScopedPool::new(&mut storage.0, 8);
+
+    let buffers: Vec<_> = core::iter::from_fn(|| pool.get()).collect();
+
+    assert_eq!(pool.count(), 4);
+    assert_eq!(buffers.len(), 4);
+    assert!(pool.get().is_none());
+
+    drop(buffers);
+    let mut buffer = pool.get().unwrap();
+    buffer.extend_from_slice(&[0x01, 0x02, 0x03]).unwrap();
+    buffer.insert(0, 0x00).unwrap();
+    buffer.retain(|&byte| byte != 0x03);
+    buffer[0] = 0x04;
+
+    assert_eq!(buffer, [0x04, 0x01, 0x02]);
+    assert_eq!(buffer.capacity(), 8);
+    assert_eq!(buffer.remaining(), 5);
+
+    let clone = buffer.try_clone().unwrap();
+    assert_eq!(clone, buffer);
+
+    let mut string = StrBuffer::from_utf8(clone).unwrap();
+    string.push_str("\u{3}").unwrap();
+    let mut packet = PacketBuffer::from(string.into_buffer());
+    packet.pull_front(1);
+    let mut reader = BufferReader::new(packet.into_buffer());
+    reader.skip(1);
+
+    assert_eq!(reader.remaining_slice(), &[0x02, 0x03]);
+
+    drop(reader);
+    buffer.remove(0);
+
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            buffer.push(0x03).unwrap();
+            assert_eq!(&buffer[..], &[0x01, 0x02, 0x03]);
+        });
+    });
+
+    assert!(pool.get().is_some());
+}
+
+#[test]
+fn pool_const_expr() {
+    const CAPACITY: usize = 4;
+    const COUNT: usize = 1;
+
+    static POOL: Pool = pool![[u8; CAPACITY * 2]; COUNT + 1];
+
+    let buffer_1 = POOL.get();
+    let buffer_2 = POOL.get();
+    let buffer_3 = POOL.get();
+
+    assert!(buffer_1.is_some());
+    assert!(buffer_2.is_some());
+    assert!(buffer_3.is_none());
+
+    assert_eq!(buffer_1.unwrap().capacity(), 8);
+}
+
+#[test]
+fn pool_group_get_for() {
+    static POOLS: PoolGroup<3> = pools![[[u8; 8]; 1], [[u8; 16]; 1], [[u8; 32]; 1]];
+
+    let buffer_1 = POOLS.get_for(4);
+    let buffer_2 = POOLS.get_for(4);
+    let buffer_3 = POOLS.get_for(20);
+    let buffer_4 = POOLS.get_for(4);
+
+    assert!(matches!(buffer_1, Some(ref buffer) if buffer.capacity() == 8));
+    assert!(matches!(buffer_2, Some(ref buffer) if buffer.capacity() == 16));
+    assert!(matches!(buffer_3, Some(ref buffer) if buffer.capacity() == 32));
+    assert!(buffer_4.is_none());
+    assert!(POOLS.get_for(40).is_none());
+
+    drop(buffer_1);
+
+    let buffer_1 = POOLS.get_for(1);
+
+    assert!(matches!(buffer_1, Some(ref buffer) if buffer.capacity() == 8));
+}
+
+#[test]
+#[allow(clippy::redundant_pattern_matching)]
 fn multi_threaded() {
     use std;
     use std::thread::{sleep, spawn};
@@ -262,3 +937,480 @@ fn multi_threaded() {
         .into_iter()
         .for_each(|handle| handle.join().unwrap());
 }
+
+#[test]
+fn multi_threaded_contention() {
+    use std;
+    use std::thread::{spawn, yield_now};
+    use std::vec::Vec;
+
+    static POOL: Pool = pool![[u8; 8]; 4];
+
+    let handles: Vec<_> = (0..8u8)
+        .map(|id| {
+            spawn(move || {
+                for _ in 0..100_000 {
+                    if let Some(mut buffer) = POOL.get() {
+                        buffer.extend_from_slice(&[id; 8]).unwrap();
+
+                        yield_now();
+
+                        // If another thread got hold of the same buffer, the content
+                        // would have been overwritten.
+                        assert_eq!(buffer.as_ref(), &[id; 8]);
+                    }
+                }
+            })
+        })
+        .collect();
+
+    handles
+        .into_iter()
+        .for_each(|handle| handle.join().unwrap());
+
+    // All buffers must have made it back to the free list exactly once.
+    let buffers: Vec<_> = (0..4).map(|_| POOL.get()).collect();
+    assert!(buffers.iter().all(|buffer| buffer.is_some()));
+    assert!(POOL.get().is_none());
+}
+
+/// Link the buffers of a pool with three buffers as A -> B -> C, then get a buffer while
+/// A and B are popped and A is pushed back between reading the next index of A and
+/// swapping the head. Returns that buffer, B and the buffer that is handed out next.
+#[cfg(test)]
+fn pool_aba(pool: &'static Pool, untagged: bool) -> (Buffer, Buffer, Option<Buffer>) {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let (a, b, c) = (
+        pool.get().unwrap(),
+        pool.get().unwrap(),
+        pool.get().unwrap(),
+    );
+    drop(c);
+    drop(b);
+    drop(a);
+
+    let held = Rc::new(RefCell::new(None));
+    let hook_held = held.clone();
+
+    UNTAGGED.set(untagged);
+    POP_HOOK.set(Some(Box::new(move || {
+        let a = pool.get().unwrap();
+        *hook_held.borrow_mut() = pool.get();
+        drop(a);
+    })));
+
+    let first = pool.get().unwrap();
+    let next = pool.get();
+    UNTAGGED.set(false);
+
+    let b = held.take().unwrap();
+    (first, b, next)
+}
+
+#[test]
+fn pool_aba_untagged() {
+    static POOL: Pool = pool![[u8; 8]; 3];
+
+    let (first, b, next) = pool_aba(&POOL, true);
+
+    // The swap succeeded with the stale next index, so B is handed out a second time.
+    assert_ne!(first.as_ptr(), b.as_ptr());
+    assert_eq!(next.unwrap().as_ptr(), b.as_ptr());
+
+    // The slot of B is now held twice, never return it to the pool.
+    core::mem::forget(b);
+}
+
+#[test]
+fn pool_aba_tagged() {
+    static POOL: Pool = pool![[u8; 8]; 3];
+
+    let (first, b, next) = pool_aba(&POOL, false);
+    let next = next.unwrap();
+
+    // The swap failed because the tag changed, so every buffer is handed out once.
+    assert_ne!(first.as_ptr(), b.as_ptr());
+    assert_ne!(next.as_ptr(), b.as_ptr());
+    assert_ne!(next.as_ptr(), first.as_ptr());
+    assert!(POOL.get().is_none());
+}
+
+#[test]
+fn static_pool_get() {
+    static POOL: StaticPool<8, 2> = StaticPool::new();
+
+    let buffer_1 = POOL.get();
+    let buffer_2 = POOL.get();
+    let buffer_3 = POOL.get();
+
+    assert!(buffer_1.is_some());
+    assert!(buffer_2.is_some());
+    assert!(buffer_3.is_none());
+
+    let mut buffer_1 = buffer_1.unwrap();
+
+    assert_eq!(buffer_1.capacity(), 8);
+
+    let result_1 = buffer_1.extend_from_slice(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+    let result_2 = buffer_1.extend_from_slice(&[0x07, 0x08, 0x09]);
+
+    assert!(result_1.is_ok());
+    assert!(matches!(result_2, Err(&[0x09])));
+    assert_eq!(buffer_1.remaining(), 0);
+
+    let buffer_1: Buffer = buffer_1.into();
+
+    assert_eq!(buffer_1.capacity(), 8);
+    assert_eq!(
+        buffer_1.as_ref(),
+        &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]
+    );
+
+    let mut buffer_2 = buffer_2.unwrap();
+
+    assert_eq!(buffer_2.resize(6), Ok(()));
+    assert_eq!(buffer_2.push(0x07), Ok(()));
+    assert_eq!(buffer_2.push(0x08), Ok(()));
+    assert_eq!(buffer_2.push(0x09), Err(0x09));
+    assert_eq!(buffer_2.pop(), Some(0x08));
+    assert_eq!(buffer_2.resize(10), Err(2));
+    assert_eq!(
+        &buffer_2[..],
+        &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00]
+    );
+    assert_eq!(buffer_2.resize(2), Ok(()));
+    assert_eq!(&buffer_2[..], &[0x00, 0x00]);
+
+    drop(buffer_1);
+    drop(buffer_2);
+
+    let buffer_1 = POOL.get();
+    let buffer_2 = POOL.get();
+    let buffer_3 = POOL.get();
+
+    assert!(buffer_1.is_some());
+    assert!(buffer_2.is_some());
+    assert!(buffer_3.is_none());
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn pool_stats() {
+    static POOL: Pool = pool![[u8; 8]; 2];
+
+    let stats = POOL.stats();
+
+    assert_eq!(stats.free, 2);
+    assert_eq!(stats.in_use, 0);
+    assert_eq!(stats.peak_in_use, 0);
+
+    let buffer_1 = POOL.get();
+    let buffer_2 = POOL.get();
+    let buffer_3 = POOL.get();
+
+    assert!(buffer_3.is_none());
+
+    let stats = POOL.stats();
+
+    assert_eq!(stats.free, 0);
+    assert_eq!(stats.in_use, 2);
+    assert_eq!(stats.peak_in_use, 2);
+    assert_eq!(stats.gets, 3);
+    assert_eq!(stats.failed_gets, 1);
+
+    drop(buffer_1);
+    drop(buffer_2);
+
+    let _buffer_1 = POOL.get();
+
+    let stats = POOL.stats();
+
+    assert_eq!(stats.free, 1);
+    assert_eq!(stats.in_use, 1);
+    assert_eq!(stats.peak_in_use, 2);
+    assert_eq!(stats.gets, 4);
+    assert_eq!(stats.failed_gets, 1);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn pool_acquire() {
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let buffer = POOL.get().unwrap();
+
+    let mut future = pin!(POOL.acquire());
+
+    assert!(matches!(future.as_mut().poll(&mut cx), Poll::Pending));
+    assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+
+    drop(buffer);
+
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    assert!(matches!(future.as_mut().poll(&mut cx), Poll::Ready(_)));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn pool_acquire_many() {
+    use core::future::Future;
+    use core::task::{Context, Poll, Waker};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    // More tasks than there are waker slots.
+    let mut tasks: Vec<_> = (0..10)
+        .map(|_| {
+            let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+            (
+                Waker::from(counter.clone()),
+                counter,
+                Box::pin(POOL.acquire()),
+            )
+        })
+        .collect();
+
+    let mut buffer = POOL.get();
+    for (waker, _, future) in &mut tasks {
+        let poll = future.as_mut().poll(&mut Context::from_waker(waker));
+        assert!(poll.is_pending());
+    }
+
+    // Every release wakes the waiting tasks until one of them gets the buffer, and none
+    // of them is polled again without a release.
+    while !tasks.is_empty() {
+        assert!(tasks
+            .iter()
+            .all(|(_, counter, _)| counter.0.load(Ordering::SeqCst) == 0));
+
+        drop(buffer.take());
+
+        let mut polls = 0;
+        while let Some(index) = tasks
+            .iter()
+            .position(|(_, counter, _)| counter.0.swap(0, Ordering::SeqCst) > 0)
+        {
+            polls += 1;
+            assert!(
+                polls <= 2 * tasks.len(),
+                "tasks are polled without a release"
+            );
+
+            let (waker, _, future) = &mut tasks[index];
+            if let Poll::Ready(ready) = future.as_mut().poll(&mut Context::from_waker(waker)) {
+                assert!(buffer.replace(ready).is_none());
+                drop(tasks.remove(index));
+            }
+        }
+
+        assert!(buffer.is_some());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn pool_get_timeout() {
+    use std::thread::{sleep, spawn};
+    use std::time::Duration;
+
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let buffer = POOL.get_blocking();
+
+    assert!(POOL.get_timeout(Duration::from_millis(10)).is_none());
+
+    let handle = spawn(|| POOL.get_timeout(Duration::from_secs(10)).is_some());
+
+    sleep(Duration::from_millis(10));
+    drop(buffer);
+
+    assert!(handle.join().unwrap());
+
+    let handle = spawn(|| drop(POOL.get_blocking()));
+
+    handle.join().unwrap();
+}
+
+#[cfg(all(feature = "std", feature = "stats"))]
+#[test]
+fn pool_stats_get_timeout() {
+    use std::thread::{sleep, spawn};
+    use std::time::Duration;
+
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let buffer = POOL.get_blocking();
+
+    assert!(POOL.get_timeout(Duration::from_millis(10)).is_none());
+
+    let handle = spawn(|| drop(POOL.get_timeout(Duration::from_secs(10))));
+
+    sleep(Duration::from_millis(10));
+    drop(buffer);
+    handle.join().unwrap();
+
+    let stats = POOL.stats();
+
+    assert_eq!(stats.in_use, 0);
+    assert_eq!(stats.peak_in_use, 1);
+    assert_eq!(stats.gets, 3);
+    assert_eq!(stats.failed_gets, 1);
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn buffer_bytes() {
+    use ::bytes::{Buf, BufMut};
+
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let mut buffer = POOL.get().unwrap();
+
+    buffer.put_u16(0x0102);
+    buffer.put_slice(&[0x03, 0x04]);
+
+    assert_eq!(buffer.remaining_mut(), 4);
+    assert_eq!(buffer.as_ref(), &[0x01, 0x02, 0x03, 0x04]);
+
+    let mut reader = BufferReader::new(buffer);
+
+    assert_eq!(reader.get_u8(), 0x01);
+    assert_eq!(reader.get_u16(), 0x0203);
+    assert_eq!(Buf::remaining(&reader), 1);
+    assert_eq!(reader.chunk(), &[0x04]);
+}
+
+#[cfg(feature = "embedded-dma")]
+#[test]
+fn buffer_embedded_dma() {
+    use ::embedded_dma::{ReadBuffer, WriteBuffer};
+
+    static POOL: Pool = pool![[u8; 8]; 2];
+
+    let mut buffer = POOL.get().unwrap();
+
+    let (ptr, len) = unsafe { buffer.write_buffer() };
+
+    assert_eq!(len, 8);
+
+    unsafe { ptr.copy_from_nonoverlapping([0x01, 0x02, 0x03].as_ptr(), 3) };
+    unsafe { buffer.set_len(3) };
+
+    let (ptr, len) = unsafe { buffer.read_buffer() };
+
+    assert_eq!(len, 3);
+    assert_eq!(
+        unsafe { core::slice::from_raw_parts(ptr, len) },
+        &[0x01, 0x02, 0x03]
+    );
+}
+
+#[cfg(feature = "embedded-io")]
+#[test]
+fn buffer_embedded_io() {
+    use ::embedded_io::{BufRead, ErrorKind, Read, Write};
+
+    static POOL: Pool = pool![[u8; 8]; 1];
+
+    let mut buffer = POOL.get().unwrap();
+
+    let result_1 = buffer.write(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+    let result_2 = buffer.write(&[0x07, 0x08, 0x09]);
+    let result_3 = buffer.write(&[0x0A]);
+    let result_4 = buffer.write(&[]);
+
+    assert!(matches!(result_1, Ok(6)));
+    assert!(matches!(result_2, Ok(2)));
+    assert!(matches!(result_3, Err(ErrorKind::WriteZero)));
+    assert!(matches!(result_4, Ok(0)));
+
+    let mut reader = BufferReader::new(buffer);
+    let mut bytes = [0x00; 3];
+
+    assert!(matches!(reader.read(&mut bytes), Ok(3)));
+    assert_eq!(bytes, [0x01, 0x02, 0x03]);
+
+    reader.consume(1);
+
+    assert!(matches!(reader.fill_buf(), Ok(&[0x05, 0x06, 0x07, 0x08])));
+    assert!(matches!(reader.read(&mut bytes), Ok(3)));
+    assert!(matches!(reader.read(&mut bytes), Ok(1)));
+    assert!(matches!(reader.read(&mut bytes), Ok(0)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn buffer_std_io() {
+    use std::io::{BufRead, Read, Seek, SeekFrom, Write};
+
+    static POOL: Pool = pool![[u8; 8]; 2];
+
+    let mut buffer = POOL.get().unwrap();
+
+    let result_1 = buffer.write(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+    let result_2 = buffer.write(&[0x07, 0x08, 0x09]);
+    let result_3 = buffer.write(&[0x0A]);
+
+    assert!(matches!(result_1, Ok(6)));
+    assert!(matches!(result_2, Ok(2)));
+    assert!(matches!(result_3, Ok(0)));
+
+    let mut reader = BufferReader::new(buffer);
+    let mut bytes = [0x00; 3];
+
+    assert!(matches!(reader.read(&mut bytes), Ok(3)));
+    assert_eq!(bytes, [0x01, 0x02, 0x03]);
+
+    reader.consume(1);
+
+    assert!(matches!(reader.fill_buf(), Ok(&[0x05, 0x06, 0x07, 0x08])));
+    assert!(matches!(reader.seek(SeekFrom::End(-2)), Ok(6)));
+    assert!(matches!(reader.seek(SeekFrom::Current(-1)), Ok(5)));
+    assert!(matches!(reader.seek(SeekFrom::Start(10)), Ok(8)));
+    assert!(reader.seek(SeekFrom::Current(-9)).is_err());
+
+    reader.rewind().unwrap();
+
+    let mut buffer = POOL.get().unwrap();
+
+    buffer.push(0xFF).unwrap();
+
+    assert!(matches!(buffer.read_from(&mut reader), Ok(7)));
+    assert!(matches!(buffer.read_from(&mut reader), Ok(0)));
+    assert_eq!(
+        buffer.as_ref(),
+        &[0xFF, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07]
+    );
+}
@@ -0,0 +1,200 @@
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+
+use crate::{ScopedBuffer, SharedBuffer};
+
+/// A buffer with room in front of the data to prepend headers.
+///
+/// Each protocol layer can prepend its header with [`push_front`](PacketBuffer::push_front)
+/// or strip it with [`pull_front`](PacketBuffer::pull_front) without moving the payload.
+///
+/// ```
+/// # use lebuf::{Pool, pool};
+/// static POOL: Pool = pool![[u8; 256]; 8];
+///
+/// // Reserve room for the headers of the lower layers.
+/// let mut packet = POOL.get_packet(16).unwrap();
+///
+/// packet.extend_from_slice(b"payload").unwrap();
+/// packet.push_front(&[0x45, 0x00]).unwrap();
+///
+/// assert_eq!(packet.as_ref(), b"\x45\x00payload");
+/// assert_eq!(packet.pull_front(2), Some(&[0x45, 0x00][..]));
+/// assert_eq!(packet.as_ref(), b"payload");
+/// ```
+pub struct PacketBuffer<'a> {
+    /// The buffer backing the packet. Its length marks the end of the data.
+    buffer: ScopedBuffer<'a>,
+    /// The index of the start of the data.
+    head: usize,
+}
+
+impl core::fmt::Debug for PacketBuffer<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(&self[..]).finish()
+    }
+}
+
+impl Deref for PacketBuffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.buffer.ptr.add(self.head), self.len()) }
+    }
+}
+
+impl DerefMut for PacketBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { core::slice::from_raw_parts_mut(self.buffer.ptr.add(self.head), self.len()) }
+    }
+}
+
+impl<'a> From<ScopedBuffer<'a>> for PacketBuffer<'a> {
+    /// Convert a buffer into a packet buffer without headroom.
+    fn from(buffer: ScopedBuffer<'a>) -> Self {
+        PacketBuffer { buffer, head: 0 }
+    }
+}
+
+impl<'a> PacketBuffer<'a> {
+    /// Returns the capacity of the buffer, including headroom and tailroom.
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    /// Returns the length of the data in the buffer.
+    pub fn len(&self) -> usize {
+        self.buffer.len - self.head
+    }
+
+    /// Returns `true` if the buffer is empty, i.e. its len is 0.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the space in front of the data.
+    pub fn headroom(&self) -> usize {
+        self.head
+    }
+
+    /// Returns the space after the data.
+    pub fn tailroom(&self) -> usize {
+        self.buffer.remaining()
+    }
+
+    /// Make sure there are at least `headroom` bytes in front of the data, moving the data
+    /// towards the end of the buffer if needed. Returns an error with the number of bytes
+    /// that are missing if the tailroom is too small.
+    pub fn reserve_head(&mut self, headroom: usize) -> Result<(), usize> {
+        if headroom <= self.head {
+            return Ok(());
+        }
+
+        let shift = headroom - self.head;
+        if shift > self.tailroom() {
+            return Err(shift - self.tailroom());
+        }
+
+        unsafe {
+            let head = self.buffer.ptr.add(self.head);
+            ptr::copy(head, head.add(shift), self.len());
+        }
+        self.head += shift;
+        self.buffer.len += shift;
+
+        Ok(())
+    }
+
+    /// Prepend the slice to the data. If the headroom is too small, nothing is written and
+    /// an error is returned containing the slice.
+    pub fn push_front<'b>(&mut self, other: &'b [u8]) -> Result<(), &'b [u8]> {
+        if other.len() > self.head {
+            return Err(other);
+        }
+
+        self.head -= other.len();
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(), self.buffer.ptr.add(self.head), other.len())
+        };
+
+        Ok(())
+    }
+
+    /// Remove `n` bytes from the front of the data, turning them into headroom. Returns the
+    /// removed bytes, or `None` if the buffer contains less than `n` bytes.
+    pub fn pull_front(&mut self, n: usize) -> Option<&[u8]> {
+        if n > self.len() {
+            return None;
+        }
+
+        let head = self.head;
+        self.head += n;
+        Some(unsafe { core::slice::from_raw_parts(self.buffer.ptr.add(head), n) })
+    }
+
+    /// Push a single byte to the end of the data. If this would exceed the capacity of the
+    /// buffer, an error is returned containing the byte that could not be written.
+    pub fn push(&mut self, byte: u8) -> Result<(), u8> {
+        self.extend_from_slice(&[byte]).map_err(|_| byte)
+    }
+
+    /// Pop the last byte from the data. If the buffer is empty, `None` is returned.
+    pub fn pop(&mut self) -> Option<u8> {
+        self.pull_back(1).map(|bytes| bytes[0])
+    }
+
+    /// Append the slice to the data. If this would exceed the capacity of the buffer,
+    /// an error will be returned containing a slice of the bytes that could not be written.
+    pub fn extend_from_slice<'b>(&mut self, other: &'b [u8]) -> Result<(), &'b [u8]> {
+        let added_len = self.tailroom().min(other.len());
+        unsafe {
+            ptr::copy_nonoverlapping(
+                other.as_ptr(),
+                self.buffer.ptr.add(self.buffer.len),
+                added_len,
+            )
+        };
+        self.buffer.len += added_len;
+        if added_len == other.len() {
+            Ok(())
+        } else {
+            Err(&other[added_len..])
+        }
+    }
+
+    /// Remove `n` bytes from the end of the data, turning them into tailroom. Returns the
+    /// removed bytes, or `None` if the buffer contains less than `n` bytes.
+    pub fn pull_back(&mut self, n: usize) -> Option<&[u8]> {
+        if n > self.len() {
+            return None;
+        }
+
+        self.buffer.len -= n;
+        Some(unsafe { core::slice::from_raw_parts(self.buffer.ptr.add(self.buffer.len), n) })
+    }
+
+    /// Shorten the data to `len` bytes. Has no effect if `len` is greater than the
+    /// current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.buffer.len = self.head + len;
+        }
+    }
+
+    /// Convert into a buffer, moving the data to the start of the buffer.
+    pub fn into_buffer(self) -> ScopedBuffer<'a> {
+        let PacketBuffer { mut buffer, head } = self;
+        let len = buffer.len - head;
+        unsafe { ptr::copy(buffer.ptr.add(head), buffer.ptr, len) };
+        buffer.len = len;
+        buffer
+    }
+}
+
+impl PacketBuffer<'static> {
+    /// Convert into an immutable buffer that can be cheaply cloned, without moving
+    /// the data.
+    pub fn freeze(self) -> SharedBuffer {
+        SharedBuffer::new(self.buffer, self.head)
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::time::Instant;
+
+/// Parks threads that are waiting for a buffer to be released.
+pub(crate) struct Parking {
+    /// The number of threads that are waiting.
+    waiters: AtomicUsize,
+    /// Lock that is held by a waiting thread between trying to get a buffer and
+    /// going to sleep, so a release can't slip in between.
+    lock: Mutex<()>,
+    /// Condition variable on which the waiting threads sleep.
+    condvar: Condvar,
+}
+
+impl Parking {
+    /// Create a new parking place without waiting threads.
+    pub(crate) const fn new() -> Self {
+        Self {
+            waiters: AtomicUsize::new(0),
+            lock: Mutex::new(()),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// Call `f` until it returns a value, parking the thread in between attempts. Returns
+    /// `None` if the deadline passes first.
+    pub(crate) fn park<T>(
+        &self,
+        mut f: impl FnMut() -> Option<T>,
+        deadline: Option<Instant>,
+    ) -> Option<T> {
+        if let Some(value) = f() {
+            return Some(value);
+        }
+
+        let mut guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // Make sure the increment is visible before trying again, a release checks
+        // for waiting threads after it pushed the buffer back.
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        std::sync::atomic::fence(Ordering::SeqCst);
+
+        let value = loop {
+            if let Some(value) = f() {
+                break Some(value);
+            }
+
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break None;
+                    }
+                    guard = self
+                        .condvar
+                        .wait_timeout(guard, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                }
+                None => {
+                    guard = self
+                        .condvar
+                        .wait(guard)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        };
+
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+
+        value
+    }
+
+    /// Wake up a waiting thread, if any.
+    pub(crate) fn unpark(&self) {
+        if self.waiters.load(Ordering::SeqCst) > 0 {
+            // Taking the lock guarantees the waiting thread is asleep on the condition
+            // variable and not in between its last attempt and going to sleep.
+            drop(self.lock.lock().unwrap_or_else(PoisonError::into_inner));
+            self.condvar.notify_one();
+        }
+    }
+}
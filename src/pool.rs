@@ -1,122 +1,182 @@
 use core::cell::UnsafeCell;
-use core::mem::size_of;
-use core::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::{Buffer, Inner};
+use crate::{Buffer, Inner, PacketBuffer, Storage};
 
 /// A memory pool that hands out statically allocated buffers.
 pub struct Pool {
+    /// Raw pointer to the start of the backing array.
+    base: *mut u8,
     inner: UnsafeCell<Inner>,
 }
 
 impl Pool {
-    /// For a given data index, get the next data index.
+    /// Create a new pool
     ///
     /// # Safety
     ///
-    /// The index that is being passed needs to be part of the linked list of free buffers.
-    unsafe fn next(&self, data: usize) -> usize {
-        (((*self.inner.get()).get_ptr)(data) as *const usize).read_unaligned()
-    }
-
-    /// Get the length of the backing array.
-    fn backing_len(&self) -> usize {
-        unsafe { (*self.inner.get()).backing_len }
+    /// `storage` must not be used by any other pool, and must be created with
+    /// [`Storage::new`].
+    pub const unsafe fn new<const CAP: usize, const N: usize, A>(
+        storage: &'static Storage<CAP, N, A>,
+    ) -> Self {
+        Self::new_padded(storage, CAP)
     }
 
-    /// Get the capacity of the buffer capacity.
-    fn buffer_capacity(&self) -> usize {
-        unsafe { (*self.inner.get()).capacity }
+    /// Create a new pool with buffers of `capacity` bytes, where every slot of `storage`
+    /// is padded to `STRIDE` bytes.
+    ///
+    /// # Safety
+    ///
+    /// `storage` must not be used by any other pool, and must be created with
+    /// [`Storage::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is greater than `STRIDE`.
+    pub const unsafe fn new_padded<const STRIDE: usize, const N: usize, A>(
+        storage: &'static Storage<STRIDE, N, A>,
+        capacity: usize,
+    ) -> Self {
+        Self {
+            base: storage.as_ptr(),
+            inner: UnsafeCell::new(Inner::new(STRIDE * N, capacity, STRIDE, true)),
+        }
     }
 
-    /// Create a new pool
+    /// Create a new pool like [`new_padded`](Pool::new_padded), over storage that may be
+    /// uninitialized. Every buffer is zeroed the first time it is handed out.
     ///
     /// # Safety
     ///
-    /// `backing` raw pointer must point to a static byte array with length `backing_len`.
-    pub const unsafe fn new(
-        backing: fn(usize) -> *mut u8,
-        backing_len: usize,
+    /// `storage` must not be used by any other pool.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is greater than `STRIDE`.
+    pub const unsafe fn new_uninit<const STRIDE: usize, const N: usize, A>(
+        storage: &'static Storage<STRIDE, N, A>,
         capacity: usize,
     ) -> Self {
-        assert!(capacity >= size_of::<usize>());
+        Self {
+            base: storage.as_ptr(),
+            inner: UnsafeCell::new(Inner::new(STRIDE * N, capacity, STRIDE, false)),
+        }
+    }
 
+    /// Create a new pool at runtime over `storage`, with buffers of `capacity` bytes. The
+    /// metadata of the pool is carved out of `storage` as well, as many buffers as fit in
+    /// the rest of it are used. The pool can be made available with a
+    /// [`PoolCell`](crate::PoolCell).
+    ///
+    /// Storage aligned to a `usize` fits `storage.len() / (capacity + size_of::<usize>())`
+    /// buffers if `capacity` is a multiple of a `usize`, unaligned storage may fit one
+    /// buffer less. [`count`](Pool::count) returns the number of buffers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is smaller than a `usize`.
+    pub fn from_slice(storage: &'static mut [u8], capacity: usize) -> Self {
+        let (base, inner) = Inner::from_slice(storage, capacity);
         Self {
-            inner: UnsafeCell::new(Inner {
-                get_ptr: backing,
-                backing_len,
-                capacity,
-                linked: AtomicUsize::new(usize::MAX),
-                unlinked: AtomicUsize::new(0),
-            }),
+            base,
+            inner: UnsafeCell::new(inner),
         }
     }
 
+    /// Returns the capacity of the buffers in the pool.
+    pub const fn capacity(&self) -> usize {
+        unsafe { (*self.inner.get()).capacity }
+    }
+
+    /// Returns the number of buffers in the pool.
+    pub const fn count(&self) -> usize {
+        unsafe { (*self.inner.get()).count() }
+    }
+
     /// Get a buffer. Returns `None` if there are no available buffers.
     pub fn get(&'static self) -> Option<Buffer> {
-        // Get the unlinked data index. This can be done with `Relaxed` memory ordering
-        // because there are no other changes that we need to acquire.
-        let mut unlinked = unsafe { (*self.inner.get()).unlinked.load(Ordering::Relaxed) };
-
-        loop {
-            // Check if the unlinked index is smaller than the length of the backing array.
-            if unlinked < self.backing_len() {
-                // Calculate the next unlinked index.
-                let next_unlinked = unlinked + self.buffer_capacity();
-
-                // Swap the unlinked index with next unlinked index. This can be done with
-                // `Relaxed` memory ordering because there are no other changes we need
-                // to release or acquire.
-                match unsafe {
-                    (*self.inner.get()).unlinked.compare_exchange(
-                        unlinked,
-                        next_unlinked,
-                        Ordering::Relaxed,
-                        Ordering::Relaxed,
-                    )
-                } {
-                    // The swap succeeded so we create the buffer.
-                    Ok(data) => return Some(Buffer::new(data, &self.inner)),
-                    // The swap failed so we get the next unlinked index and try again.
-                    Err(next_unlinked) => {
-                        unlinked = next_unlinked;
-                    }
-                }
-            // The init index is greater than the backing array, so all
-            // buffers are now part of the linked list of free buffers.
-            } else {
-                // Get the linked data index. This is done with `Acquire` memory ordering
-                // because we need to make sure the next index contained inside the slice is
-                // correct.
-                let mut linked = unsafe { (*self.inner.get()).linked.load(Ordering::Acquire) };
-
-                loop {
-                    // Check if the linked index is smaller than the length of the backing array.
-                    if linked < self.backing_len() {
-                        // Get the index of the next linked slice.
-                        let next_linked = unsafe { self.next(linked) };
-
-                        // Replace the linked index with the next linked index. In case this swap
-                        // fails we'll acquire all other changes because we'll need to get a
-                        // new next linked index.
-                        match unsafe {
-                            (*self.inner.get()).linked.compare_exchange(
-                                linked,
-                                next_linked,
-                                Ordering::Relaxed,
-                                Ordering::Acquire,
-                            )
-                        } {
-                            Ok(data) => return Some(Buffer::new(data, &self.inner)),
-                            Err(next_linked) => linked = next_linked,
-                        }
-                    // No buffers are available.
-                    } else {
-                        return None;
-                    }
-                }
-            }
-        }
+        let buffer = self.take();
+
+        unsafe { (*self.inner.get()).record_get(buffer.is_some()) };
+
+        buffer
+    }
+
+    /// Take a buffer without recording a call to get one in the usage statistics.
+    fn take(&'static self) -> Option<Buffer> {
+        unsafe { (*self.inner.get()).take(self.base) }
+            .map(|data| unsafe { Buffer::new(self.base.add(data), data, &self.inner) })
+    }
+
+    /// Get a packet buffer with `headroom` bytes reserved in front of the data. Returns
+    /// `None` if there are no available buffers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `headroom` is greater than the capacity of the buffers.
+    pub fn get_packet(&'static self, headroom: usize) -> Option<PacketBuffer<'static>> {
+        self.get().map(|buffer| {
+            let mut packet = PacketBuffer::from(buffer);
+            packet
+                .reserve_head(headroom)
+                .expect("headroom exceeds buffer capacity");
+            packet
+        })
+    }
+
+    /// Get a buffer, waiting for one to be released if there are no available buffers.
+    ///
+    /// Any number of tasks can wait for the same pool. All of them are woken when a buffer
+    /// is released, and they aren't polled again until then.
+    ///
+    /// ```
+    /// # use lebuf::{Pool, pool};
+    /// static POOL: Pool = pool![[u8; 256]; 8];
+    ///
+    /// async fn receive() {
+    ///     let mut buffer = POOL.acquire().await;
+    ///     buffer.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]).unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn acquire(&'static self) -> Buffer {
+        let mut waiter = crate::Waiter::new(unsafe { &(*self.inner.get()).wakers });
+        let buffer = core::future::poll_fn(move |cx| waiter.poll(|| self.take(), cx)).await;
+
+        unsafe { (*self.inner.get()).record_get(true) };
+
+        buffer
+    }
+
+    /// Get a buffer, blocking the current thread until one is released if there are
+    /// no available buffers.
+    #[cfg(feature = "std")]
+    pub fn get_blocking(&'static self) -> Buffer {
+        let buffer = unsafe { (*self.inner.get()).parking.park(|| self.take(), None) }
+            .unwrap_or_else(|| unreachable!("waiting without a deadline can't time out"));
+
+        unsafe { (*self.inner.get()).record_get(true) };
+
+        buffer
+    }
+
+    /// Get a buffer, blocking the current thread until one is released if there are
+    /// no available buffers. Returns `None` if no buffer was released within `timeout`.
+    #[cfg(feature = "std")]
+    pub fn get_timeout(&'static self, timeout: std::time::Duration) -> Option<Buffer> {
+        let deadline = std::time::Instant::now().checked_add(timeout);
+        let buffer = unsafe { (*self.inner.get()).parking.park(|| self.take(), deadline) };
+
+        unsafe { (*self.inner.get()).record_get(buffer.is_some()) };
+
+        buffer
+    }
+
+    /// Get a snapshot of the usage statistics of the pool.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> crate::PoolStats {
+        let inner = unsafe { &*self.inner.get() };
+        inner.stats.snapshot(inner.count())
     }
 }
 
@@ -132,23 +192,87 @@ unsafe impl Send for Pool {}
 /// // Create a buffer pool with 16 buffers that each have a capacity of 256 bytes.
 /// static POOL: Pool = pool![[u8; 256]; 16];
 /// ```
+///
+/// The capacity and count can be any constant expression.
+///
+/// ```
+/// # use lebuf::{Pool, pool};
+/// const MTU: usize = 1500;
+/// const RX_SLOTS: usize = 4;
+///
+/// static RX_POOL: Pool = pool![[u8; MTU]; RX_SLOTS];
+/// static TX_POOL: Pool = pool![[u8; MTU + 4]; 2 * RX_SLOTS];
+/// ```
+///
+/// The options below follow the count, separated by commas, and must be given in the
+/// order `align`, `section`, `zeroed`. Each of them can be left out.
+///
+/// The buffers can be aligned, for example for cache maintenance or DMA engines with
+/// alignment requirements. Every buffer then starts at a multiple of the alignment,
+/// which must be a literal power of two.
+///
+/// ```
+/// # use lebuf::{Pool, pool};
+/// static POOL: Pool = pool![[u8; 200]; 8, align = 32];
+///
+/// let buffer = POOL.get().unwrap();
+///
+/// assert_eq!(buffer.capacity(), 200);
+/// assert_eq!(buffer.as_ptr() as usize % 32, 0);
+/// ```
+///
+/// The storage of the pool can be placed in a linker section, for example to put the
+/// buffers in DMA capable memory. A section that is not initialized at startup needs
+/// `zeroed = false`, the buffers are then zeroed the first time they are handed out.
+///
+/// ```no_run
+/// # use lebuf::{Pool, pool};
+/// static DMA_POOL: Pool = pool![[u8; 256]; 8, align = 32, section = ".dtcm_bss"];
+/// static RX_POOL: Pool = pool![[u8; 1536]; 16, section = ".uninit", zeroed = false];
+/// ```
+///
+/// Options in a different order are rejected.
+///
+/// ```compile_fail
+/// # use lebuf::{Pool, pool};
+/// static POOL: Pool = pool![[u8; 256]; 8, zeroed = false, align = 32];
+/// ```
 #[macro_export]
 macro_rules! pool {
-    [[u8; $capacity:literal]; $count:literal] => {
+    [[u8; $capacity:expr]; $count:expr
+        $(, align = $align:literal)?
+        $(, section = $section:literal)?
+        $(, zeroed = $zeroed:literal)? $(,)?] => {
         {
-            unsafe {
-                $crate::Pool::new(
-                    |data: usize| {
-                        static mut ARRAY: [u8; $capacity * $count] = [0x00; $capacity * $count];
-                        (core::ptr::addr_of_mut!(ARRAY) as *mut u8).add(data)
-                    },
-                    $capacity * $count,
-                    $capacity
-                )
+            #[repr(C $(, align($align))?)]
+            struct Align;
+
+            const ZEROED: bool = true $(&& $zeroed)?;
+
+            $(#[link_section = $section])?
+            static STORAGE: $crate::Storage<
+                { ($capacity as usize).next_multiple_of(::core::mem::align_of::<Align>()) },
+                { $count },
+                Align,
+            > = if ZEROED {
+                $crate::Storage::new()
+            } else {
+                $crate::Storage::uninit()
+            };
+
+            if ZEROED {
+                unsafe { $crate::Pool::new_padded(&STORAGE, $capacity) }
+            } else {
+                unsafe { $crate::Pool::new_uninit(&STORAGE, $capacity) }
             }
         }
     };
-    [[$buffer_ty:ty; $capacity:literal]; $count:literal] => {
-        compile_error!("can only create buffers containing `u8`'s");
-    }
+    [[u8; $capacity:expr]; $count:expr, $($option:tt)*] => {
+        compile_error!(
+            "unknown or misordered pool! option; expected `align`, `section`, `zeroed` in that order"
+        )
+    };
+    [[$buffer_ty:ty; $capacity:expr]; $count:expr $(, $($option:tt)*)?] => {
+        compile_error!("can only create buffers containing `u8`'s")
+    };
 }
@@ -0,0 +1,154 @@
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::Pool;
+
+/// The pool has not been initialized.
+const EMPTY: u8 = 0;
+/// The pool is being initialized.
+const INITIALIZING: u8 = 1;
+/// The pool has been initialized.
+const READY: u8 = 2;
+
+/// A cell holding a pool that is created at runtime, for example over memory of which
+/// the size is only known at boot.
+///
+/// ```
+/// # use lebuf::{Pool, PoolCell};
+/// static POOL: PoolCell = PoolCell::new();
+///
+/// let storage = Box::leak(vec![0u8; 4096].into_boxed_slice());
+/// POOL.set(storage, 256).unwrap();
+///
+/// let buffer = POOL.get().unwrap().get().unwrap();
+/// assert_eq!(buffer.capacity(), 256);
+/// ```
+pub struct PoolCell {
+    /// Whether the pool has been initialized.
+    state: AtomicU8,
+    /// The pool, valid once the state is `READY`.
+    pool: UnsafeCell<MaybeUninit<Pool>>,
+}
+
+impl PoolCell {
+    /// Create a new cell without a pool.
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(EMPTY),
+            pool: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Returns the pool, or `None` if it has not been initialized yet.
+    pub fn get(&self) -> Option<&Pool> {
+        // Acquire the initialization of the pool.
+        if self.state.load(Ordering::Acquire) == READY {
+            Some(unsafe { (*self.pool.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Initialize the pool over `storage` with buffers of `capacity` bytes, see
+    /// [`Pool::from_slice`]. Returns `storage` as an error if the pool was already
+    /// initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is smaller than a `usize`.
+    pub fn set(
+        &self,
+        storage: &'static mut [u8],
+        capacity: usize,
+    ) -> Result<&Pool, &'static mut [u8]> {
+        if self.try_start() {
+            Ok(self.init(|| (storage, capacity)))
+        } else {
+            Err(storage)
+        }
+    }
+
+    /// Returns the pool, initializing it with the storage and capacity returned by `f` if
+    /// it has not been initialized yet. If another thread is initializing the pool, this
+    /// spins until it is done. If `f` panics the cell is left empty, so a later call can
+    /// initialize the pool.
+    ///
+    /// On a single core this spins forever when called from an interrupt handler that
+    /// preempted the initialization, use [`try_get_or_init`](PoolCell::try_get_or_init)
+    /// there instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the returned capacity is smaller than a `usize`.
+    pub fn get_or_init(&self, f: impl FnOnce() -> (&'static mut [u8], usize)) -> &Pool {
+        loop {
+            if let Some(pool) = self.get() {
+                return pool;
+            }
+            // Initialization elsewhere may have unwound, in which case it's claimed again.
+            if self.try_start() {
+                return self.init(f);
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Returns the pool, initializing it with the storage and capacity returned by `f` if
+    /// it has not been initialized yet. Returns `None` without waiting if the pool is
+    /// being initialized elsewhere, so this can be called from an interrupt handler. If
+    /// `f` panics the cell is left empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the returned capacity is smaller than a `usize`.
+    pub fn try_get_or_init(&self, f: impl FnOnce() -> (&'static mut [u8], usize)) -> Option<&Pool> {
+        if self.try_start() {
+            return Some(self.init(f));
+        }
+
+        self.get()
+    }
+
+    /// Claim the initialization of the pool. Returns `false` if the pool is already
+    /// initialized or being initialized.
+    fn try_start(&self) -> bool {
+        self.state
+            .compare_exchange(EMPTY, INITIALIZING, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// Create and store the pool after claiming its initialization. The claim is given up
+    /// if this unwinds.
+    fn init(&self, f: impl FnOnce() -> (&'static mut [u8], usize)) -> &Pool {
+        let reset = Reset(&self.state);
+        let (storage, capacity) = f();
+        let pool = Pool::from_slice(storage, capacity);
+        core::mem::forget(reset);
+
+        let pool = unsafe { (*self.pool.get()).write(pool) };
+
+        // Release the initialization of the pool to the threads that get it.
+        self.state.store(READY, Ordering::Release);
+        pool
+    }
+}
+
+impl Default for PoolCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resets the state of a cell to `EMPTY` when dropped, which only happens if its
+/// initialization unwinds.
+struct Reset<'a>(&'a AtomicU8);
+
+impl Drop for Reset<'_> {
+    fn drop(&mut self) {
+        self.0.store(EMPTY, Ordering::Release);
+    }
+}
+
+unsafe impl Sync for PoolCell {}
+unsafe impl Send for PoolCell {}
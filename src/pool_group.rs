@@ -0,0 +1,62 @@
+use crate::{Buffer, Pool};
+
+/// A group of memory pools that serve as size classes.
+///
+/// Buffers are taken from the smallest pool that can hold the requested length,
+/// falling back to larger pools when a pool is exhausted. A buffer is always returned
+/// to the pool it was taken from.
+pub struct PoolGroup<const N: usize> {
+    pools: [Pool; N],
+}
+
+impl<const N: usize> PoolGroup<N> {
+    /// Create a new pool group. The pools must be ordered by ascending capacity.
+    pub const fn new(pools: [Pool; N]) -> Self {
+        let mut i = 1;
+        while i < N {
+            assert!(
+                pools[i - 1].capacity() < pools[i].capacity(),
+                "pools must be ordered by ascending capacity"
+            );
+            i += 1;
+        }
+
+        Self { pools }
+    }
+
+    /// Get a buffer from the smallest pool with a capacity of at least `len` bytes
+    /// that has an available buffer. Returns `None` if there are no available buffers
+    /// that are large enough.
+    pub fn get_for(&'static self, len: usize) -> Option<Buffer> {
+        self.pools
+            .iter()
+            .filter(|pool| pool.capacity() >= len)
+            .find_map(|pool| pool.get())
+    }
+
+    /// Get the pools of the group, ordered by ascending capacity.
+    pub fn pools(&self) -> &[Pool] {
+        &self.pools
+    }
+}
+
+/// Macro to create a group of memory pools that serve as size classes.
+///
+/// Every pool is declared with the same syntax as [`pool!`](crate::pool), ordered by
+/// ascending capacity.
+///
+/// ```
+/// # use lebuf::{PoolGroup, pools};
+/// static POOLS: PoolGroup<3> = pools![[[u8; 64]; 16], [[u8; 512]; 8], [[u8; 2048]; 2]];
+///
+/// // Get a buffer from the pool with buffers of 512 bytes.
+/// let buffer = POOLS.get_for(100).unwrap();
+///
+/// assert_eq!(buffer.capacity(), 512);
+/// ```
+#[macro_export]
+macro_rules! pools {
+    [$([$($pool:tt)*]),+ $(,)?] => {
+        $crate::PoolGroup::new([$($crate::pool![$($pool)*]),+])
+    };
+}
@@ -0,0 +1,56 @@
+use core::cell::UnsafeCell;
+
+use crate::Inner;
+
+/// An opaque token that holds the slot of a [`Buffer`](crate::Buffer) while it is not
+/// owned by a buffer, created by [`Buffer::into_raw`](crate::Buffer::into_raw).
+///
+/// The token can be handed to another context, like an interrupt handler, and turned
+/// back into the buffer with [`Buffer::from_raw`](crate::Buffer::from_raw). Dropping the
+/// token leaks the slot, it is never returned to its pool.
+#[must_use = "dropping a raw buffer leaks its slot"]
+pub struct RawBuffer {
+    /// Raw pointer to the slice backing the buffer.
+    pub(crate) ptr: *mut u8,
+    /// The starting index of the slice backing the buffer.
+    pub(crate) data: usize,
+    /// The length of the buffer.
+    pub(crate) len: usize,
+    /// The memory pool of which this buffer is part of.
+    pub(crate) pool: &'static UnsafeCell<Inner>,
+}
+
+impl core::fmt::Debug for RawBuffer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RawBuffer")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl RawBuffer {
+    /// Returns a raw pointer to the start of the buffer. The pointer stays valid until
+    /// the buffer is dropped after being turned back with
+    /// [`Buffer::from_raw`](crate::Buffer::from_raw).
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Returns the length of the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the buffer is empty, i.e. its len is 0.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        unsafe { (*self.pool.get()).capacity }
+    }
+}
+
+unsafe impl Send for RawBuffer {}
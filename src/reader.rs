@@ -0,0 +1,59 @@
+use crate::ScopedBuffer;
+
+/// A cursor that reads the contents of a buffer from front to back.
+pub struct BufferReader<'a> {
+    /// The buffer that is being read.
+    buffer: ScopedBuffer<'a>,
+    /// The index of the next byte to read.
+    pos: usize,
+}
+
+impl core::fmt::Debug for BufferReader<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.remaining_slice()).finish()
+    }
+}
+
+impl<'a> From<ScopedBuffer<'a>> for BufferReader<'a> {
+    fn from(buffer: ScopedBuffer<'a>) -> Self {
+        Self::new(buffer)
+    }
+}
+
+impl<'a> BufferReader<'a> {
+    /// Create a new reader, positioned at the start of the buffer.
+    pub fn new(buffer: ScopedBuffer<'a>) -> Self {
+        Self { buffer, pos: 0 }
+    }
+
+    /// Returns the index of the next byte to read.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Set the index of the next byte to read. Positions past the end of the buffer
+    /// are clamped to its length.
+    pub fn set_position(&mut self, pos: usize) {
+        self.pos = pos.min(self.buffer.len());
+    }
+
+    /// Returns the bytes that have not been read yet.
+    pub fn remaining_slice(&self) -> &[u8] {
+        &self.buffer[self.pos..]
+    }
+
+    /// Skip `n` bytes, clamped to the number of bytes that have not been read yet.
+    pub fn skip(&mut self, n: usize) {
+        self.pos = (self.pos + n).min(self.buffer.len());
+    }
+
+    /// Get a reference to the buffer that is being read.
+    pub fn get_ref(&self) -> &ScopedBuffer<'a> {
+        &self.buffer
+    }
+
+    /// Convert back into the buffer that was being read.
+    pub fn into_inner(self) -> ScopedBuffer<'a> {
+        self.buffer
+    }
+}
@@ -0,0 +1,89 @@
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+
+use crate::{Inner, ScopedBuffer};
+
+/// A memory pool over borrowed storage, that hands out buffers which can't outlive it.
+///
+/// Unlike a [`Pool`](crate::Pool) this doesn't have to be a `static`, so it can be
+/// created per test, per connection or on the stack.
+///
+/// ```
+/// # use lebuf::ScopedPool;
+/// let mut storage = [0x00; 1024];
+/// let pool = ScopedPool::new(&mut storage, 64);
+///
+/// let mut buffer = pool.get().unwrap();
+/// buffer.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]).unwrap();
+///
+/// assert_eq!(&buffer[..], &[0x01, 0x02, 0x03, 0x04]);
+/// ```
+///
+/// A buffer can't outlive the pool it was taken from.
+///
+/// ```compile_fail
+/// # use lebuf::ScopedPool;
+/// let buffer = {
+///     let mut storage = [0x00; 1024];
+///     let pool = ScopedPool::new(&mut storage, 64);
+///     pool.get().unwrap()
+/// };
+/// ```
+pub struct ScopedPool<'a> {
+    /// Raw pointer to the start of the backing array.
+    base: *mut u8,
+    inner: UnsafeCell<Inner>,
+    /// The storage the pool borrows.
+    storage: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> ScopedPool<'a> {
+    /// Create a new pool over `storage`, with buffers of `capacity` bytes. The metadata of
+    /// the pool is carved out of `storage` as well, as many buffers as fit in the rest of
+    /// it are used.
+    ///
+    /// Storage aligned to a `usize` fits `storage.len() / (capacity + size_of::<usize>())`
+    /// buffers if `capacity` is a multiple of a `usize`, unaligned storage may fit one
+    /// buffer less. [`count`](ScopedPool::count) returns the number of buffers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is smaller than a `usize`.
+    pub fn new(storage: &'a mut [u8], capacity: usize) -> Self {
+        let (base, inner) = Inner::from_slice(storage, capacity);
+        Self {
+            base,
+            inner: UnsafeCell::new(inner),
+            storage: PhantomData,
+        }
+    }
+
+    /// Returns the capacity of the buffers in the pool.
+    pub fn capacity(&self) -> usize {
+        unsafe { (*self.inner.get()).capacity }
+    }
+
+    /// Returns the number of buffers in the pool.
+    pub fn count(&self) -> usize {
+        unsafe { (*self.inner.get()).count() }
+    }
+
+    /// Get a buffer. Returns `None` if there are no available buffers.
+    pub fn get(&self) -> Option<ScopedBuffer<'_>> {
+        let data = unsafe { (*self.inner.get()).take(self.base) };
+
+        unsafe { (*self.inner.get()).record_get(data.is_some()) };
+
+        data.map(|data| unsafe { ScopedBuffer::new(self.base.add(data), data, &self.inner) })
+    }
+
+    /// Get a snapshot of the usage statistics of the pool.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> crate::PoolStats {
+        let inner = unsafe { &*self.inner.get() };
+        inner.stats.snapshot(inner.count())
+    }
+}
+
+unsafe impl Sync for ScopedPool<'_> {}
+unsafe impl Send for ScopedPool<'_> {}
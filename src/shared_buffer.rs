@@ -0,0 +1,158 @@
+use core::cell::UnsafeCell;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, RangeBounds};
+use core::sync::atomic::{fence, AtomicUsize, Ordering};
+
+use crate::buffer::bounds;
+use crate::{Buffer, Inner};
+
+/// An immutable statically allocated buffer that can be cheaply cloned.
+///
+/// A shared buffer is a view into a buffer of a pool. Views of different parts of the
+/// same buffer can be created without copying with [`slice`](SharedBuffer::slice),
+/// [`split_to`](SharedBuffer::split_to) and [`split_off`](SharedBuffer::split_off).
+/// The buffer is returned to its pool when the last view is dropped.
+pub struct SharedBuffer {
+    /// Raw pointer to the slice backing the buffer.
+    ptr: *mut u8,
+    /// The starting index of the slice backing the buffer.
+    data: usize,
+    /// The offset of this view within the slice backing the buffer.
+    offset: usize,
+    /// The length of this view.
+    len: usize,
+    /// The memory pool of which this buffer is part of.
+    pool: &'static UnsafeCell<Inner>,
+}
+
+impl core::fmt::Debug for SharedBuffer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(&self[..]).finish()
+    }
+}
+
+impl Deref for SharedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.ptr.add(self.offset), self.len) }
+    }
+}
+
+impl SharedBuffer {
+    /// Create a new shared buffer, viewing the contents of `buffer` from `offset`.
+    pub(crate) fn new(buffer: Buffer, offset: usize) -> Self {
+        assert!(offset <= buffer.len);
+
+        let buffer = ManuallyDrop::new(buffer);
+        let shared = SharedBuffer {
+            ptr: buffer.ptr,
+            data: buffer.data,
+            offset,
+            len: buffer.len - offset,
+            pool: buffer.pool,
+        };
+
+        // Nobody else has access to the reference count yet.
+        shared.refs().store(1, Ordering::Relaxed);
+
+        shared
+    }
+
+    /// Get the reference count of the buffer.
+    fn refs(&self) -> &AtomicUsize {
+        unsafe { (*self.pool.get()).refs(self.ptr, self.data) }
+    }
+
+    /// Returns the length of the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the buffer is empty, i.e. its len is 0.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a view of the given range of the buffer, without copying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> SharedBuffer {
+        let (start, end) = bounds(range, self.len);
+
+        let mut view = self.clone();
+        view.offset += start;
+        view.len = end - start;
+        view
+    }
+
+    /// Splits the buffer in two at the given index, without copying. Afterwards `self`
+    /// contains `[at, len)` and the returned buffer contains `[0, at)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_to(&mut self, at: usize) -> SharedBuffer {
+        assert!(at <= self.len, "split index out of bounds");
+
+        let mut head = self.clone();
+        head.len = at;
+        self.offset += at;
+        self.len -= at;
+        head
+    }
+
+    /// Splits the buffer in two at the given index, without copying. Afterwards `self`
+    /// contains `[0, at)` and the returned buffer contains `[at, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> SharedBuffer {
+        assert!(at <= self.len, "split index out of bounds");
+
+        let mut tail = self.clone();
+        tail.offset += at;
+        tail.len -= at;
+        self.len = at;
+        tail
+    }
+}
+
+impl From<Buffer> for SharedBuffer {
+    fn from(buffer: Buffer) -> Self {
+        SharedBuffer::new(buffer, 0)
+    }
+}
+
+impl Clone for SharedBuffer {
+    fn clone(&self) -> Self {
+        // A new reference can only be created from an existing one, so `Relaxed`
+        // memory ordering is sufficient.
+        self.refs().fetch_add(1, Ordering::Relaxed);
+
+        SharedBuffer {
+            ptr: self.ptr,
+            data: self.data,
+            offset: self.offset,
+            len: self.len,
+            pool: self.pool,
+        }
+    }
+}
+
+impl Drop for SharedBuffer {
+    fn drop(&mut self) {
+        // Release our use of the buffer, the last reference acquires all uses of
+        // the other references before returning the buffer to the pool.
+        if self.refs().fetch_sub(1, Ordering::Release) == 1 {
+            fence(Ordering::Acquire);
+            unsafe { (*self.pool.get()).release(self.ptr, self.data) };
+        }
+    }
+}
+
+unsafe impl Send for SharedBuffer {}
+unsafe impl Sync for SharedBuffer {}
@@ -0,0 +1,114 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::Buffer;
+
+/// A statically allocated buffer with a capacity of `CAP` bytes, handed out by a
+/// [`StaticPool`](crate::StaticPool).
+pub struct StaticBuffer<const CAP: usize> {
+    buffer: Buffer,
+}
+
+impl<const CAP: usize> core::fmt::Debug for StaticBuffer<CAP> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.buffer.fmt(f)
+    }
+}
+
+impl<const CAP: usize> Deref for StaticBuffer<CAP> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.slice()[..self.buffer.len]
+    }
+}
+
+impl<const CAP: usize> DerefMut for StaticBuffer<CAP> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let len = self.buffer.len;
+        &mut self.slice_mut()[..len]
+    }
+}
+
+impl<const CAP: usize> StaticBuffer<CAP> {
+    /// Create a new buffer.
+    ///
+    /// # Safety
+    ///
+    /// The capacity of `buffer` must be `CAP`.
+    pub(crate) unsafe fn new(buffer: Buffer) -> Self {
+        Self { buffer }
+    }
+
+    /// Get a reference to the slice backing the buffer. The capacity is known from the
+    /// type, so it isn't read from the pool.
+    fn slice(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.buffer.ptr, CAP) }
+    }
+
+    /// Get a mutable reference to the slice backing the buffer.
+    fn slice_mut(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.buffer.ptr, CAP) }
+    }
+
+    /// Returns the capacity of the buffer.
+    pub const fn capacity(&self) -> usize {
+        CAP
+    }
+
+    /// Returns the length of the buffer.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// # Safety
+    ///
+    /// New length must be smaller than buffer capacity.
+    pub unsafe fn set_len(&mut self, len: usize) {
+        assert!(len <= CAP);
+        self.buffer.len = len;
+    }
+
+    /// Returns `true` if the buffer is empty, i.e. its len is 0.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Returns the remaining space in the buffer.
+    pub fn remaining(&self) -> usize {
+        CAP - self.buffer.len()
+    }
+
+    /// Push a single byte to the end of the buffer. If this would exceed the
+    /// capacity of the buffer, an error is returned containing the byte that
+    /// could not be written.
+    pub fn push(&mut self, byte: u8) -> Result<(), u8> {
+        self.buffer.push_within(CAP, byte)
+    }
+
+    /// Pop the last byte from the buffer. If the buffer is empty, `None` is returned.
+    pub fn pop(&mut self) -> Option<u8> {
+        self.buffer.pop()
+    }
+
+    /// Resize the buffer. Returns an error with the number of bytes that could not be written.
+    pub fn resize(&mut self, size: usize) -> Result<(), usize> {
+        self.buffer.resize_within(CAP, size)
+    }
+
+    /// Append the slice to the buffer. If this would exceed the capacity of the buffer,
+    /// an error will be returned containing a slice of the bytes that could not be written.
+    pub fn extend_from_slice<'a>(&mut self, other: &'a [u8]) -> Result<(), &'a [u8]> {
+        self.buffer.extend_from_slice_within(CAP, other)
+    }
+
+    /// Convert into a [`Buffer`], erasing the capacity from the type.
+    pub fn into_buffer(self) -> Buffer {
+        self.buffer
+    }
+}
+
+impl<const CAP: usize> From<StaticBuffer<CAP>> for Buffer {
+    fn from(buffer: StaticBuffer<CAP>) -> Self {
+        buffer.into_buffer()
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{Buffer, RawBuffer};
+
+/// A guard that holds the slot of a [`Buffer`] while its contents are lent out with a
+/// `'static` lifetime, created by [`Buffer::lease`].
+///
+/// The buffer is only given back by [`release`](StaticLease::release), in exchange for
+/// the `'static` borrow. Because the borrow is unique this proves that it is no longer
+/// in use. Dropping the lease instead leaks the slot, so the borrow stays valid forever.
+///
+/// ```
+/// # use lebuf::{Pool, pool};
+/// static POOL: Pool = pool![[u8; 256]; 8];
+///
+/// let mut buffer = POOL.get().unwrap();
+/// buffer.resize(16).unwrap();
+///
+/// let (lease, borrow) = buffer.lease();
+///
+/// // Hand `borrow` to a DMA transfer or an interrupt handler and get it back.
+/// borrow[0] = 0x01;
+///
+/// let buffer = lease.release(borrow);
+/// assert_eq!(buffer[0], 0x01);
+/// ```
+#[must_use = "dropping a lease leaks its slot"]
+#[derive(Debug)]
+pub struct StaticLease {
+    /// The slot of the leased buffer.
+    raw: RawBuffer,
+}
+
+impl StaticLease {
+    /// Lease the contents of `buffer` with a `'static` lifetime.
+    pub(crate) fn new(buffer: Buffer) -> (Self, &'static mut [u8]) {
+        let raw = buffer.into_raw();
+        let borrow = unsafe { core::slice::from_raw_parts_mut(raw.ptr, raw.len) };
+        (Self { raw }, borrow)
+    }
+
+    /// Returns the leased buffer in exchange for the `'static` borrow of its contents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `borrow` is not the borrow that was handed out with this lease.
+    pub fn release(self, borrow: &'static mut [u8]) -> Buffer {
+        assert!(
+            borrow.as_ptr() == self.raw.ptr.cast_const() && borrow.len() == self.raw.len,
+            "borrow does not belong to this lease"
+        );
+
+        Buffer::from_raw(self.raw)
+    }
+}
@@ -0,0 +1,126 @@
+use core::cell::UnsafeCell;
+use core::mem::size_of;
+
+use crate::{Buffer, Inner, StaticBuffer, Storage};
+
+/// A memory pool with `N` buffers that each have a capacity of `CAP` bytes. The
+/// capacity is part of the type of the buffers that are handed out.
+///
+/// ```
+/// # use lebuf::{StaticBuffer, StaticPool};
+/// // A buffer pool with 8 buffers, each with a capacity of 256 bytes.
+/// static POOL: StaticPool<256, 8> = StaticPool::new();
+///
+/// fn fill(buffer: &mut StaticBuffer<256>) {
+///     buffer.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]).unwrap();
+/// }
+///
+/// let mut buffer = POOL.get().unwrap();
+/// fill(&mut buffer);
+/// ```
+///
+/// The capacity of a buffer must be large enough to hold a `usize`, this is checked at
+/// compile time.
+///
+/// ```compile_fail
+/// # use lebuf::StaticPool;
+/// static POOL: StaticPool<2, 8> = StaticPool::new();
+/// ```
+pub struct StaticPool<const CAP: usize, const N: usize> {
+    inner: UnsafeCell<Inner>,
+    /// The memory backing the buffers.
+    storage: Storage<CAP, N>,
+}
+
+impl<const CAP: usize, const N: usize> StaticPool<CAP, N> {
+    /// Create a new pool.
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                CAP >= size_of::<usize>(),
+                "buffer capacity must be able to hold a `usize`"
+            )
+        };
+
+        Self {
+            inner: UnsafeCell::new(Inner::new(CAP * N, CAP, CAP, true)),
+            storage: Storage::new(),
+        }
+    }
+
+    /// Get a raw pointer to the start of the backing array.
+    fn base(&self) -> *mut u8 {
+        self.storage.as_ptr()
+    }
+
+    /// Get a buffer. Returns `None` if there are no available buffers.
+    pub fn get(&'static self) -> Option<StaticBuffer<CAP>> {
+        let buffer = self.take();
+
+        unsafe { (*self.inner.get()).record_get(buffer.is_some()) };
+
+        buffer
+    }
+
+    /// Take a buffer without recording a call to get one in the usage statistics.
+    fn take(&'static self) -> Option<StaticBuffer<CAP>> {
+        unsafe { (*self.inner.get()).take(self.base()) }.map(|data| {
+            let buffer = unsafe { Buffer::new(self.base().add(data), data, &self.inner) };
+            unsafe { StaticBuffer::new(buffer) }
+        })
+    }
+
+    /// Get a buffer, waiting for one to be released if there are no available buffers.
+    ///
+    /// Any number of tasks can wait for the same pool. All of them are woken when a buffer
+    /// is released, and they aren't polled again until then.
+    #[cfg(feature = "async")]
+    pub async fn acquire(&'static self) -> StaticBuffer<CAP> {
+        let mut waiter = crate::Waiter::new(unsafe { &(*self.inner.get()).wakers });
+        let buffer = core::future::poll_fn(move |cx| waiter.poll(|| self.take(), cx)).await;
+
+        unsafe { (*self.inner.get()).record_get(true) };
+
+        buffer
+    }
+
+    /// Get a buffer, blocking the current thread until one is released if there are
+    /// no available buffers.
+    #[cfg(feature = "std")]
+    pub fn get_blocking(&'static self) -> StaticBuffer<CAP> {
+        let buffer = unsafe { (*self.inner.get()).parking.park(|| self.take(), None) }
+            .unwrap_or_else(|| unreachable!("waiting without a deadline can't time out"));
+
+        unsafe { (*self.inner.get()).record_get(true) };
+
+        buffer
+    }
+
+    /// Get a buffer, blocking the current thread until one is released if there are
+    /// no available buffers. Returns `None` if no buffer was released within `timeout`.
+    #[cfg(feature = "std")]
+    pub fn get_timeout(&'static self, timeout: std::time::Duration) -> Option<StaticBuffer<CAP>> {
+        let deadline = std::time::Instant::now().checked_add(timeout);
+        let buffer = unsafe { (*self.inner.get()).parking.park(|| self.take(), deadline) };
+
+        unsafe { (*self.inner.get()).record_get(buffer.is_some()) };
+
+        buffer
+    }
+
+    /// Get a snapshot of the usage statistics of the pool.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> crate::PoolStats {
+        let inner = unsafe { &*self.inner.get() };
+        inner.stats.snapshot(inner.count())
+    }
+}
+
+impl<const CAP: usize, const N: usize> Default for StaticPool<CAP, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const CAP: usize, const N: usize> Sync for StaticPool<CAP, N> {}
+unsafe impl<const CAP: usize, const N: usize> Send for StaticPool<CAP, N> {}
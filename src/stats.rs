@@ -0,0 +1,73 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A snapshot of the usage statistics of a [`Pool`](crate::Pool).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    /// The number of buffers that are available.
+    pub free: usize,
+    /// The number of buffers that are currently in use.
+    pub in_use: usize,
+    /// The highest number of buffers that were in use at the same time.
+    pub peak_in_use: usize,
+    /// The total number of calls to get a buffer. A call that waits for a buffer, like
+    /// [`Pool::acquire`](crate::Pool::acquire), counts once however often it retries.
+    pub gets: usize,
+    /// The number of calls to get a buffer that returned `None`.
+    pub failed_gets: usize,
+}
+
+/// Counters that keep track of the usage of a pool.
+pub(crate) struct Stats {
+    /// The number of buffers that are currently in use.
+    in_use: AtomicUsize,
+    /// The highest number of buffers that were in use at the same time.
+    peak_in_use: AtomicUsize,
+    /// The total number of calls to get a buffer.
+    gets: AtomicUsize,
+    /// The number of calls to get a buffer that failed.
+    failed_gets: AtomicUsize,
+}
+
+impl Stats {
+    /// Create new counters.
+    pub(crate) const fn new() -> Self {
+        Self {
+            in_use: AtomicUsize::new(0),
+            peak_in_use: AtomicUsize::new(0),
+            gets: AtomicUsize::new(0),
+            failed_gets: AtomicUsize::new(0),
+        }
+    }
+
+    /// Record a call to get a buffer. The counters are only used for reporting, so
+    /// `Relaxed` memory ordering is sufficient.
+    pub(crate) fn record_get(&self, success: bool) {
+        self.gets.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.failed_gets.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record a buffer being taken from the pool.
+    pub(crate) fn record_take(&self) {
+        let in_use = self.in_use.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak_in_use.fetch_max(in_use, Ordering::Relaxed);
+    }
+
+    /// Record a buffer being returned to the pool.
+    pub(crate) fn record_release(&self) {
+        self.in_use.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Take a snapshot of the counters for a pool with `count` buffers.
+    pub(crate) fn snapshot(&self, count: usize) -> PoolStats {
+        let in_use = self.in_use.load(Ordering::Relaxed);
+        PoolStats {
+            free: count.saturating_sub(in_use),
+            in_use,
+            peak_in_use: self.peak_in_use.load(Ordering::Relaxed),
+            gets: self.gets.load(Ordering::Relaxed),
+            failed_gets: self.failed_gets.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use core::cell::UnsafeCell;
+use core::mem::{offset_of, MaybeUninit};
+use core::sync::atomic::AtomicUsize;
+
+use crate::Inner;
+
+/// The memory backing a pool with `N` buffers that each take up `CAP` bytes.
+///
+/// The buffers are followed by the metadata of the pool, a reference count for every
+/// buffer. The backing array is aligned to the alignment of `A`.
+#[repr(C)]
+pub struct Storage<const CAP: usize, const N: usize, A = ()> {
+    /// Aligns the backing array, without taking up any space.
+    align: [A; 0],
+    /// The array backing the buffers.
+    slots: UnsafeCell<MaybeUninit<[[u8; CAP]; N]>>,
+    /// The reference count of every buffer.
+    refs: MaybeUninit<[AtomicUsize; N]>,
+}
+
+impl<const CAP: usize, const N: usize, A> Storage<CAP, N, A> {
+    /// Create new zero-initialized storage.
+    pub const fn new() -> Self {
+        // The pool locates the buffers and metadata based on the start of the storage.
+        assert!(offset_of!(Self, slots) == 0);
+        assert!(offset_of!(Self, refs) == Inner::refs_offset(CAP * N));
+
+        Self {
+            align: [],
+            slots: UnsafeCell::new(MaybeUninit::new([[0x00; CAP]; N])),
+            refs: MaybeUninit::new([const { AtomicUsize::new(0) }; N]),
+        }
+    }
+
+    /// Create new uninitialized storage, for a pool created with
+    /// [`Pool::new_uninit`](crate::Pool::new_uninit). This allows the storage to be placed
+    /// in a linker section that is not initialized at startup.
+    pub const fn uninit() -> Self {
+        assert!(offset_of!(Self, slots) == 0);
+        assert!(offset_of!(Self, refs) == Inner::refs_offset(CAP * N));
+
+        Self {
+            align: [],
+            slots: UnsafeCell::new(MaybeUninit::uninit()),
+            refs: MaybeUninit::uninit(),
+        }
+    }
+
+    /// Get a raw pointer to the start of the backing array.
+    pub(crate) const fn as_ptr(&self) -> *mut u8 {
+        self as *const Self as *mut u8
+    }
+}
+
+impl<const CAP: usize, const N: usize, A> Default for Storage<CAP, N, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const CAP: usize, const N: usize, A> Sync for Storage<CAP, N, A> {}
+unsafe impl<const CAP: usize, const N: usize, A> Send for Storage<CAP, N, A> {}
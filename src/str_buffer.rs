@@ -0,0 +1,142 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::ScopedBuffer;
+
+/// A buffer that always contains valid UTF-8.
+///
+/// Writes that exceed the capacity are truncated at a character boundary, so a code
+/// point is never split.
+///
+/// ```
+/// # use lebuf::{Pool, StrBuffer, pool};
+/// use core::fmt::Write;
+///
+/// static POOL: Pool = pool![[u8; 16]; 8];
+///
+/// let mut string = StrBuffer::from_utf8(POOL.get().unwrap()).unwrap();
+///
+/// write!(string, "AT+CSQ={}", 31).unwrap();
+///
+/// assert_eq!(&*string, "AT+CSQ=31");
+/// ```
+pub struct StrBuffer<'a> {
+    buffer: ScopedBuffer<'a>,
+}
+
+impl core::fmt::Debug for StrBuffer<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl core::fmt::Display for StrBuffer<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl Deref for StrBuffer<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl DerefMut for StrBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { core::str::from_utf8_unchecked_mut(&mut self.buffer) }
+    }
+}
+
+impl<'a> StrBuffer<'a> {
+    /// Convert a buffer into a string buffer. Returns the buffer as an error if it does
+    /// not contain valid UTF-8.
+    pub fn from_utf8(buffer: ScopedBuffer<'a>) -> Result<Self, ScopedBuffer<'a>> {
+        match core::str::from_utf8(&buffer) {
+            Ok(_) => Ok(StrBuffer { buffer }),
+            Err(_) => Err(buffer),
+        }
+    }
+
+    /// Returns the contents of the buffer as a string slice.
+    pub fn as_str(&self) -> &str {
+        unsafe { core::str::from_utf8_unchecked(&self.buffer) }
+    }
+
+    /// Returns the capacity of the buffer in bytes.
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    /// Returns the length of the buffer in bytes.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns `true` if the buffer is empty, i.e. its len is 0.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Returns the remaining space in the buffer in bytes.
+    pub fn remaining(&self) -> usize {
+        self.buffer.remaining()
+    }
+
+    /// Append the string slice to the buffer. If this would exceed the capacity of the
+    /// buffer, as many characters as fit are written and an error is returned containing
+    /// the rest of the string slice.
+    pub fn push_str<'b>(&mut self, other: &'b str) -> Result<(), &'b str> {
+        let mut len = other.len().min(self.remaining());
+        while !other.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        let (head, tail) = other.split_at(len);
+        let _ = self.buffer.extend_from_slice(head.as_bytes());
+
+        if tail.is_empty() {
+            Ok(())
+        } else {
+            Err(tail)
+        }
+    }
+
+    /// Push a single character to the end of the buffer. If this would exceed the
+    /// capacity of the buffer, an error is returned containing the character.
+    pub fn push(&mut self, c: char) -> Result<(), char> {
+        if c.len_utf8() > self.remaining() {
+            return Err(c);
+        }
+
+        let _ = self.push_str(c.encode_utf8(&mut [0; 4]));
+        Ok(())
+    }
+
+    /// Pop the last character from the buffer. If the buffer is empty, `None` is returned.
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        let _ = self.buffer.resize(self.len() - c.len_utf8());
+        Some(c)
+    }
+
+    /// Convert back into a buffer.
+    pub fn into_buffer(self) -> ScopedBuffer<'a> {
+        self.buffer
+    }
+}
+
+impl core::fmt::Write for StrBuffer<'_> {
+    /// Append the string slice to the buffer, truncated at a character boundary if it
+    /// exceeds the capacity of the buffer.
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.push_str(s).map_err(|_| core::fmt::Error)
+    }
+}
+
+impl<'a> From<StrBuffer<'a>> for ScopedBuffer<'a> {
+    fn from(string: StrBuffer<'a>) -> Self {
+        string.into_buffer()
+    }
+}
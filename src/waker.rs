@@ -0,0 +1,197 @@
+use core::cell::UnsafeCell;
+use core::sync::atomic::{fence, AtomicUsize, Ordering};
+use core::task::{Context, Poll, Waker};
+
+/// The number of waker slots of a pool. Any number of tasks can wait for a buffer, the
+/// slots only limit how many of them can register their waker at the same moment.
+const WAKER_SLOTS: usize = 4;
+
+/// No waker is being registered or woken.
+const WAITING: usize = 0b00;
+/// A waker is being registered.
+const REGISTERING: usize = 0b01;
+/// The registered waker is being woken.
+const WAKING: usize = 0b10;
+
+/// A waker that can be registered and woken concurrently without locking.
+pub(crate) struct AtomicWaker {
+    /// Whether a waker is being registered and/or woken.
+    state: AtomicUsize,
+    /// The registered waker.
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+impl AtomicWaker {
+    /// Create a new atomic waker without a registered waker.
+    pub(crate) const fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(WAITING),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Register a waker to be woken by the next call to `wake`, and return the waker it
+    /// replaces. Returns `Err` if another waker is being registered or the registered
+    /// waker is being woken.
+    pub(crate) fn replace(&self, waker: &Waker) -> Result<Option<Waker>, ()> {
+        if self
+            .state
+            .compare_exchange(WAITING, REGISTERING, Ordering::Acquire, Ordering::Acquire)
+            .is_err()
+        {
+            return Err(());
+        }
+
+        // We have exclusive access to the waker cell.
+        unsafe {
+            let replaced = match (*self.waker.get()).take() {
+                Some(old) if old.will_wake(waker) => {
+                    *self.waker.get() = Some(old);
+                    None
+                }
+                old => {
+                    *self.waker.get() = Some(waker.clone());
+                    old
+                }
+            };
+
+            // If a wake happened while we were registering, we're responsible for
+            // waking the waker that was just registered.
+            if self
+                .state
+                .compare_exchange(REGISTERING, WAITING, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+            {
+                let waker = (*self.waker.get()).take();
+                self.state.swap(WAITING, Ordering::AcqRel);
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+
+            Ok(replaced)
+        }
+    }
+
+    /// Take the registered waker, if any.
+    pub(crate) fn take(&self) -> Option<Waker> {
+        match self.state.fetch_or(WAKING, Ordering::AcqRel) {
+            WAITING => {
+                let waker = unsafe { (*self.waker.get()).take() };
+                self.state.fetch_and(!WAKING, Ordering::Release);
+                waker
+            }
+            // The waker is being registered, the registering thread will wake it.
+            _ => None,
+        }
+    }
+
+    /// Wake the registered waker, if any.
+    pub(crate) fn wake(&self) {
+        if let Some(waker) = self.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The wakers of tasks that are waiting for a buffer to be released.
+///
+/// Tasks share a fixed number of slots. A task that registers its waker takes over the
+/// waker it replaces, and wakes it when it is woken itself. This way every waiting task
+/// is woken on release, without storing a waker per task in the pool.
+pub(crate) struct WakerList {
+    /// The most recently registered wakers.
+    wakers: [AtomicWaker; WAKER_SLOTS],
+}
+
+impl WakerList {
+    /// Create a new waker list without waiting tasks.
+    pub(crate) const fn new() -> Self {
+        Self {
+            wakers: [const { AtomicWaker::new() }; WAKER_SLOTS],
+        }
+    }
+
+    /// Register a waker in the first slot that is not in use by another context, and
+    /// return the waker it replaces. Returns `Err` if all slots are in use.
+    pub(crate) fn register(&self, waker: &Waker) -> Result<Option<Waker>, ()> {
+        for slot in &self.wakers {
+            if let Ok(replaced) = slot.replace(waker) {
+                return Ok(replaced);
+            }
+        }
+        Err(())
+    }
+
+    /// Wake all tasks that are waiting.
+    pub(crate) fn wake(&self) {
+        self.wakers.iter().for_each(AtomicWaker::wake);
+    }
+}
+
+unsafe impl Sync for AtomicWaker {}
+unsafe impl Send for AtomicWaker {}
+
+/// A task waiting for a buffer to be released.
+pub(crate) struct Waiter {
+    /// The wakers of the pool the task is waiting on.
+    wakers: &'static WakerList,
+    /// The waker this task replaced when it registered its own, which is woken when this
+    /// task is woken or completes.
+    replaced: Option<Waker>,
+}
+
+impl Waiter {
+    /// Create a new waiter for the given waker list.
+    pub(crate) fn new(wakers: &'static WakerList) -> Self {
+        Self {
+            wakers,
+            replaced: None,
+        }
+    }
+
+    /// Try to get a buffer with `get`, registering the waker of the task if there
+    /// are no available buffers.
+    pub(crate) fn poll<T>(
+        &mut self,
+        mut get: impl FnMut() -> Option<T>,
+        cx: &mut Context<'_>,
+    ) -> Poll<T> {
+        // Pass the wake up on to the task this one replaced, which registers its waker
+        // again if it still has to wait.
+        if let Some(waker) = self.replaced.take() {
+            waker.wake();
+        }
+
+        if let Some(buffer) = get() {
+            return Poll::Ready(buffer);
+        }
+
+        match self.wakers.register(cx.waker()) {
+            Ok(replaced) => {
+                self.replaced = replaced;
+
+                // A buffer might have been released before the waker was registered, so
+                // try one last time. See `Inner::release` for the other side of the fence.
+                fence(Ordering::SeqCst);
+                if let Some(buffer) = get() {
+                    return Poll::Ready(buffer);
+                }
+            }
+            // Every slot is being registered in or woken by another context at this very
+            // moment, which needs as many concurrent contexts as there are slots. This
+            // doesn't last, so poll again.
+            Err(()) => cx.waker().wake_by_ref(),
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        if let Some(waker) = self.replaced.take() {
+            waker.wake();
+        }
+    }
+}